@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use super::runner::Part;
+use super::utils::{Answer, FIRST_YEAR};
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io(PathBuf, io::Error),
+    Syntax(PathBuf, usize, &'static str),
+}
+
+impl Display for StoreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Io(path, e) => write!(f, "cannot access answers {}: {e}", path.display()),
+            Self::Syntax(path, line, expected) =>
+                write!(f, "answers {}, line {line}: expected {expected}", path.display()),
+        }
+    }
+}
+
+impl Error for StoreError {}
+
+/// Known answers, one `[<year>.<day>]` section per day. A bare `[<day>]` header, as written before
+/// years were supported, belongs to [`FIRST_YEAR`].
+#[derive(Default)]
+pub struct Answers {
+    answers: BTreeMap<(usize, usize, Part), Answer>,
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self, StoreError> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content)
+                .map_err(|(line, expected)| StoreError::Syntax(path.into(), line, expected)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(StoreError::Io(path.into(), e)),
+        }
+    }
+
+    fn parse(content: &str) -> Result<Self, (usize, &'static str)> {
+        let mut answers = BTreeMap::new();
+        let mut day = None;
+        for (i, line) in content.lines().enumerate().map(|(i, l)| (i + 1, l.trim())) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                day = Some(parse_header(header.trim()).ok_or((i, "a `<year>.<day>` header"))?);
+                continue;
+            }
+            let (year, day) = day.ok_or((i, "a `[<year>.<day>]` header"))?;
+            let (key, value) = line.split_once('=').ok_or((i, "`<part> = <answer>`"))?;
+            let part = Part::parse(key.trim()).ok_or((i, "`a` or `b`"))?;
+            let answer = parse_answer(value.trim()).ok_or((i, "a number or a quoted string"))?;
+            answers.insert((year, day, part), answer);
+        }
+        Ok(Self { answers })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), StoreError> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| StoreError::Io(dir.into(), e))?;
+        }
+        fs::write(path, self.to_string()).map_err(|e| StoreError::Io(path.into(), e))
+    }
+
+    pub fn get(&self, year: usize, day: usize, part: Part) -> Option<&Answer> {
+        self.answers.get(&(year, day, part))
+    }
+
+    pub fn insert(&mut self, year: usize, day: usize, part: Part, answer: Answer) {
+        self.answers.insert((year, day, part), answer);
+    }
+}
+
+fn parse_header(header: &str) -> Option<(usize, usize)> {
+    match header.split_once('.') {
+        Some((year, day)) => Some((year.trim().parse().ok()?, day.trim().parse().ok()?)),
+        None => Some((FIRST_YEAR, header.parse().ok()?)),
+    }
+}
+
+fn parse_answer(value: &str) -> Option<Answer> {
+    if let Some(text) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        let mut result = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => result.push(chars.next().filter(|&c| c == '"' || c == '\\')?),
+                '"' => return None,
+                c => result.push(c),
+            }
+        }
+        Some(result.into())
+    } else {
+        value.parse::<i64>().ok().map(Answer::from)
+    }
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let mut current = None;
+        for (&(year, day, part), answer) in &self.answers {
+            if current != Some((year, day)) {
+                if current.is_some() {
+                    writeln!(f)?;
+                }
+                writeln!(f, "[{year}.{day}]")?;
+                current = Some((year, day));
+            }
+            match answer {
+                Answer::Number(n) => writeln!(f, "{part} = {n}")?,
+                Answer::Text(s) => {
+                    let escaped = s.replace('\\', "\\\\").replace('"', "\\\"");
+                    writeln!(f, "{part} = \"{escaped}\"")?;
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut answers = Answers::default();
+        answers.insert(2024, 23, Part::B, "co,\"de\"\\ka".into());
+        answers.insert(2024, 1, Part::A, 11.into());
+        answers.insert(2025, 1, Part::B, Answer::from(-31));
+        let parsed = Answers::parse(&answers.to_string()).unwrap();
+        assert_eq!(parsed.answers, answers.answers);
+    }
+
+    #[test]
+    fn legacy_headers() {
+        let answers = Answers::parse("[1]\na = 11\n\n[2025.1]\na = 12").unwrap();
+        assert_eq!(answers.get(FIRST_YEAR, 1, Part::A), Some(&Answer::from(11)));
+        assert_eq!(answers.get(2025, 1, Part::A), Some(&Answer::from(12)));
+        assert!(answers.to_string().starts_with(&format!("[{FIRST_YEAR}.1]\n")));
+    }
+
+    #[test]
+    fn syntax_errors() {
+        assert_eq!(Answers::parse("a = 1").err(), Some((1, "a `[<year>.<day>]` header")));
+        assert_eq!(Answers::parse("[x.1]").err(), Some((1, "a `<year>.<day>` header")));
+        assert_eq!(Answers::parse("[1]\n\nc = 1").err(), Some((3, "`a` or `b`")));
+        assert_eq!(Answers::parse("[1]\na = \"x").err(), Some((2, "a number or a quoted string")));
+    }
+}
@@ -0,0 +1,376 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::thread;
+
+use advent_of_code::answers::ANSWERS_FILE;
+use advent_of_code::runner::{self, Day, Format, Part, Target, Year};
+use advent_of_code::utils::{DATA_DIR_VAR, Input, data_dir};
+
+pub const USAGE: &str = "\
+usage: advent_of_code [options] <day> [a|b]
+       advent_of_code [options] all
+       advent_of_code [options] bench <day> [a|b]
+       advent_of_code [options] bench all
+       advent_of_code [options] check <day> [a|b]
+       advent_of_code [options] check all
+       advent_of_code [options] watch <day>
+       advent_of_code [options] fetch <day>
+       advent_of_code [options] gen <day> [<size>]
+       advent_of_code [options] debug
+       advent_of_code [options] trace [<limit>]
+       advent_of_code [options] brute [<count>]
+       advent_of_code [-y <year>] new <day>
+options:
+  -y, --year <year>       puzzle year, defaults to the most recent one with solutions
+  -i, --input <file>      read the puzzle input from <file>, or from stdin if <file> is `-`
+  -d, --data-dir <dir>    read puzzle inputs from <dir>/<year>/<day>.txt, defaults to $AOC_DATA_DIR
+                          or `data`
+  -t, --time              report parse and solve time for each part of a single-day run
+  -f, --format <format>   print results as `human` text, `json` or a `markdown` table, with
+                          times and peak memory, defaults to `human`
+  -n, --repeat <count>    run each part <count> times when benchmarking, defaults to 10
+  -r, --record            store the answers in the answers file
+  -j, --jobs <count>      run <count> parts at once with `all`, or <count> threads with `brute`,
+                          defaults to the number of CPUs
+  -a, --answers <file>    known answers file, defaults to <dir>/answers.toml
+  -s, --seed <seed>       random seed for `gen`, defaults to 0
+`fetch` stores missing inputs in <dir>, using the session token and base URL from $AOC_SESSION
+and $AOC_BASE_URL or `session` and `base_url` in <dir>/config.toml; the base URL defaults to
+https://adventofcode.com, which is fetched with `curl`, and may also be a plain `http://` mirror
+`gen` writes a random input for day 9, 12, 16, 20, 23 or 24 to <dir>, as large as the official
+one unless <size> is given
+`debug` steps through the day 17 program, type `help` at its prompt for the commands
+`trace` prints each instruction the day 17 program executes, up to <limit>, 10000 by default
+`brute` times checking the first <count> values of A for day 17 part b, 10000000 by default,
+with the interpreter and with the compiled program";
+
+const DEFAULT_REPEAT: usize = 10;
+
+const DEFAULT_CANDIDATES: i64 = 10_000_000;
+
+const DEFAULT_TRACE_LIMIT: usize = 10_000;
+
+pub struct Cli {
+    pub command: Command,
+    pub input: Input,
+    pub data_dir: PathBuf,
+    pub answers: PathBuf,
+}
+
+pub enum Command {
+    Run { target: Target, timed: bool, record: bool, format: Format },
+    All { year: &'static Year, record: bool, jobs: usize, format: Format },
+    Bench { target: Target, repeat: usize },
+    Check { target: Target },
+    Watch { year: &'static Year, day: &'static Day },
+    Fetch { year: usize, day: usize },
+    New { year: usize, day: usize },
+    Gen { year: usize, day: usize, size: Option<usize>, seed: u64 },
+    Debug { year: usize },
+    Trace { year: usize, limit: usize },
+    Brute { year: usize, count: i64, jobs: usize },
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Mode {
+    Run,
+    Bench,
+    Check,
+}
+
+#[derive(Default)]
+struct Options {
+    timed: bool,
+    record: bool,
+    repeat: Option<usize>,
+    jobs: Option<usize>,
+    year: Option<usize>,
+    seed: Option<u64>,
+    format: Option<Format>,
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    MissingDay,
+    UnknownYear(usize),
+    UnknownDay(String),
+    UnknownPart(String),
+    MissingPart(usize, Part),
+    UnexpectedArgument(String),
+    MissingValue(&'static str),
+    InvalidValue(&'static str, String),
+    InputForAll,
+    WatchStdin,
+    DebugStdin,
+    OptionOnlyWith(&'static str, &'static str),
+    Conflict(&'static str, &'static str),
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::MissingDay => write!(f, "missing day"),
+            Self::UnknownYear(y) => write!(f, "no solutions for year {y}"),
+            Self::UnknownDay(d) => write!(f, "unknown day `{d}`, expected 1-25 or `all`"),
+            Self::UnknownPart(p) => write!(f, "unknown part `{p}`, expected `a` or `b`"),
+            Self::MissingPart(d, p) => write!(f, "day {d} has no part {p}"),
+            Self::UnexpectedArgument(a) => write!(f, "unexpected argument `{a}`"),
+            Self::MissingValue(o) => write!(f, "missing value for `{o}`"),
+            Self::InvalidValue(o, v) => write!(f, "invalid value `{v}` for `{o}`"),
+            Self::InputForAll => write!(f, "`--input` can only be used with a single day"),
+            Self::WatchStdin => write!(f, "cannot watch stdin, pass a file to `--input`"),
+            Self::DebugStdin =>
+                write!(f, "the debugger reads commands from stdin, pass a file to `--input`"),
+            Self::OptionOnlyWith(o, c) => write!(f, "`{o}` can only be used with {c}"),
+            Self::Conflict(a, b) => write!(f, "`{a}` and `{b}` cannot be used together"),
+        }
+    }
+}
+
+impl Error for CliError {}
+
+impl Cli {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, CliError> {
+        let mut positional = Vec::new();
+        let mut input = None;
+        let mut dir = None;
+        let mut answers = None;
+        let mut options = Options::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-i" | "--input" => {
+                    let path = args.next().ok_or(CliError::MissingValue("--input"))?;
+                    input = Some(if path == "-" { Input::Stdin } else { Input::File(path.into()) });
+                },
+                "-d" | "--data-dir" => {
+                    dir = Some(args.next().ok_or(CliError::MissingValue("--data-dir"))?.into());
+                },
+                "-a" | "--answers" => {
+                    answers = Some(args.next().ok_or(CliError::MissingValue("--answers"))?.into());
+                },
+                "-t" | "--time" => options.timed = true,
+                "-r" | "--record" => options.record = true,
+                "-n" | "--repeat" => options.repeat = Some(parse_count(&mut args, "--repeat")?),
+                "-j" | "--jobs" => options.jobs = Some(parse_count(&mut args, "--jobs")?),
+                "-y" | "--year" => options.year = Some(parse_count(&mut args, "--year")?),
+                "-s" | "--seed" => options.seed = Some(parse_seed(&mut args)?),
+                "-f" | "--format" => {
+                    let format = args.next().ok_or(CliError::MissingValue("--format"))?;
+                    let parsed = Format::parse(&format);
+                    options.format =
+                        Some(parsed.ok_or(CliError::InvalidValue("--format", format))?);
+                },
+                _ => positional.push(arg),
+            }
+        }
+        let command = Command::parse(positional.into_iter(), &options)?;
+        let all = match &command {
+            Command::Run { target, .. }
+            | Command::Bench { target, .. }
+            | Command::Check { target } => matches!(target, Target::All(_)),
+            Command::All { .. } => true,
+            Command::Watch { .. }
+            | Command::Fetch { .. }
+            | Command::New { .. }
+            | Command::Gen { .. }
+            | Command::Debug { .. }
+            | Command::Trace { .. }
+            | Command::Brute { .. } => false,
+        };
+        let given_input = input.is_some();
+        if given_input && dir.is_some() {
+            return Err(CliError::Conflict("--input", "--data-dir"));
+        }
+        if all && given_input {
+            return Err(CliError::InputForAll);
+        }
+        if given_input && matches!(command, Command::Fetch { .. } | Command::Gen { .. }) {
+            return Err(CliError::OptionOnlyWith("--input", "solution runs"));
+        }
+        if matches!(input, Some(Input::Stdin)) && matches!(command, Command::Watch { .. }) {
+            return Err(CliError::WatchStdin);
+        }
+        if matches!(input, Some(Input::Stdin)) && matches!(command, Command::Debug { .. }) {
+            return Err(CliError::DebugStdin);
+        }
+        let input = input
+            .or_else(|| dir.clone().map(Input::DataDir))
+            .unwrap_or_else(|| Input::from_env(DATA_DIR_VAR));
+        let data_dir = dir.unwrap_or_else(|| data_dir(DATA_DIR_VAR));
+        let answers = answers.unwrap_or_else(|| data_dir.join(ANSWERS_FILE));
+        Ok(Self { command, input, data_dir, answers })
+    }
+}
+
+impl Command {
+    fn parse(mut args: impl Iterator<Item = String>, options: &Options) -> Result<Self, CliError> {
+        let first = args.next().ok_or(CliError::MissingDay)?;
+        let untimed =
+            ["all", "bench", "check", "watch", "fetch", "new", "gen", "debug", "trace", "brute"];
+        if options.timed && untimed.contains(&first.as_str()) {
+            return Err(CliError::OptionOnlyWith("--time", "a single-day run"));
+        }
+        if first != "gen" && options.seed.is_some() {
+            return Err(CliError::OptionOnlyWith("--seed", "`gen`"));
+        }
+        if first == "gen" {
+            let year = options.year.unwrap_or_else(|| runner::latest_year().number);
+            let day = args.next().ok_or(CliError::MissingDay)?;
+            let Ok(day @ 1..=25) = day.parse() else { return Err(CliError::UnknownDay(day)) };
+            let size = Self::parse_positive("<size>", args.next())?;
+            if let Some(a) = args.next() {
+                return Err(CliError::UnexpectedArgument(a));
+            }
+            return Ok(Self::Gen { year, day, size, seed: options.seed.unwrap_or(0) });
+        }
+        if first == "new" || first == "fetch" {
+            let year = options.year.unwrap_or_else(|| runner::latest_year().number);
+            let day = Self::parse_day_number(args)?;
+            return Ok(if first == "new" {
+                Self::New { year, day }
+            } else {
+                Self::Fetch { year, day }
+            });
+        }
+        let year = match options.year {
+            Some(y) => runner::find_year(y).ok_or(CliError::UnknownYear(y))?,
+            None => runner::latest_year(),
+        };
+        if ["debug", "trace", "brute"].contains(&first.as_str()) {
+            return Self::parse_day17(&first, year, args, options);
+        }
+        if first == "watch" {
+            let day = args.next().ok_or(CliError::MissingDay)?;
+            let day =
+                day.parse().ok().and_then(|d| year.day(d)).ok_or(CliError::UnknownDay(day))?;
+            if let Some(a) = args.next() {
+                return Err(CliError::UnexpectedArgument(a));
+            }
+            return Ok(Self::Watch { year, day });
+        }
+        let (mode, first) = match first.as_str() {
+            "bench" => (Mode::Bench, args.next().ok_or(CliError::MissingDay)?),
+            "check" => (Mode::Check, args.next().ok_or(CliError::MissingDay)?),
+            _ => (Mode::Run, first),
+        };
+        let target = Self::parse_target(year, &first, &mut args)?;
+        if let Some(a) = args.next() {
+            return Err(CliError::UnexpectedArgument(a));
+        }
+        if mode != Mode::Bench && options.repeat.is_some() {
+            return Err(CliError::OptionOnlyWith("--repeat", "`bench`"));
+        }
+        if mode != Mode::Run && options.record {
+            return Err(CliError::OptionOnlyWith("--record", "a plain run"));
+        }
+        if mode != Mode::Run && options.format.is_some() {
+            return Err(CliError::OptionOnlyWith("--format", "a plain run"));
+        }
+        let format = options.format.unwrap_or(Format::Human);
+        let all = mode == Mode::Run && matches!(target, Target::All(_));
+        if !all && options.jobs.is_some() {
+            return Err(CliError::OptionOnlyWith("--jobs", "`all` or `brute`"));
+        }
+        Ok(match mode {
+            Mode::Run if all => {
+                let jobs = options.jobs.unwrap_or_else(default_jobs);
+                Self::All { year, record: options.record, jobs, format }
+            },
+            Mode::Run => Self::Run { target, timed: options.timed, record: options.record, format },
+            Mode::Bench => Self::Bench { target, repeat: options.repeat.unwrap_or(DEFAULT_REPEAT) },
+            Mode::Check => Self::Check { target },
+        })
+    }
+
+    /// `debug`, `trace` or `brute`, which work on the day 17 program.
+    fn parse_day17(
+        first: &str, year: &Year, mut args: impl Iterator<Item = String>, options: &Options,
+    ) -> Result<Self, CliError> {
+        if year.day(17).is_none() {
+            return Err(CliError::UnknownDay("17".into()));
+        }
+        if first != "brute" && options.jobs.is_some() {
+            return Err(CliError::OptionOnlyWith("--jobs", "`all` or `brute`"));
+        }
+        let year = year.number;
+        let command = match first {
+            "trace" => {
+                let limit = Self::parse_positive("<limit>", args.next())?;
+                Self::Trace { year, limit: limit.unwrap_or(DEFAULT_TRACE_LIMIT) }
+            },
+            "brute" => {
+                let count = Self::parse_positive("<count>", args.next())?;
+                let jobs = options.jobs.unwrap_or_else(default_jobs);
+                Self::Brute { year, count: count.unwrap_or(DEFAULT_CANDIDATES), jobs }
+            },
+            _ => Self::Debug { year },
+        };
+        if let Some(a) = args.next() {
+            return Err(CliError::UnexpectedArgument(a));
+        }
+        Ok(command)
+    }
+
+    /// An optional positive number such as the size for `gen`.
+    fn parse_positive<T: FromStr + Default + PartialOrd>(
+        name: &'static str, arg: Option<String>,
+    ) -> Result<Option<T>, CliError> {
+        let parse = |s: String| match s.parse() {
+            Ok(n) if n > T::default() => Ok(n),
+            _ => Err(CliError::InvalidValue(name, s)),
+        };
+        arg.map(parse).transpose()
+    }
+
+    /// A day that need not have a solution yet, for commands that set one up.
+    fn parse_day_number(mut args: impl Iterator<Item = String>) -> Result<usize, CliError> {
+        let day = args.next().ok_or(CliError::MissingDay)?;
+        let Ok(number @ 1..=25) = day.parse() else { return Err(CliError::UnknownDay(day)) };
+        if let Some(a) = args.next() {
+            return Err(CliError::UnexpectedArgument(a));
+        }
+        Ok(number)
+    }
+
+    fn parse_target(
+        year: &'static Year, first: &str, args: &mut impl Iterator<Item = String>,
+    ) -> Result<Target, CliError> {
+        if first == "all" {
+            Ok(Target::All(year))
+        } else {
+            let day = first
+                .parse()
+                .ok()
+                .and_then(|d| year.day(d))
+                .ok_or_else(|| CliError::UnknownDay(first.into()))?;
+            let part =
+                args.next().map(|s| Part::parse(&s).ok_or(CliError::UnknownPart(s))).transpose()?;
+            if let Some(p) = part
+                && day.part(p).is_none()
+            {
+                return Err(CliError::MissingPart(day.number, p));
+            }
+            Ok(Target::Day { year, day, part })
+        }
+    }
+}
+
+fn parse_count(
+    args: &mut impl Iterator<Item = String>, option: &'static str,
+) -> Result<usize, CliError> {
+    let count = args.next().ok_or(CliError::MissingValue(option))?;
+    match count.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(CliError::InvalidValue(option, count)),
+    }
+}
+
+fn default_jobs() -> usize { thread::available_parallelism().map_or(1, NonZeroUsize::get) }
+
+fn parse_seed(args: &mut impl Iterator<Item = String>) -> Result<u64, CliError> {
+    let seed = args.next().ok_or(CliError::MissingValue("--seed"))?;
+    seed.parse().map_err(|_| CliError::InvalidValue("--seed", seed))
+}
@@ -1,31 +1,49 @@
 use std::collections::HashMap;
 
-use super::read_lines;
+use super::{Answer, Lines, ParseError};
 
-fn get_values() -> (Vec<u32>, Vec<u32>) {
-    read_lines(1)
-        .map(|s| {
-            let mut iter = s.split_whitespace().map(|s| s.parse::<u32>().unwrap());
-            (iter.next().unwrap(), iter.next().unwrap())
+pub fn get_values(input: &str) -> Result<(Vec<u32>, Vec<u32>), ParseError> {
+    Lines::new(1, input)
+        .map(|l| {
+            let mut iter = l.text.split_whitespace();
+            let pair: (u32, u32) = (l.next(&mut iter, "a number")?, l.next(&mut iter, "a number")?);
+            l.end(iter)?;
+            Ok(pair)
         })
-        .unzip()
+        .collect()
 }
 
-pub fn day1_a() {
-    let (mut left, mut right) = get_values();
+pub fn day1_a((left, right): &(Vec<u32>, Vec<u32>)) -> Answer {
+    let (mut left, mut right) = (left.clone(), right.clone());
     left.sort_unstable();
     right.sort_unstable();
     let result: u32 = left.iter().zip(right.iter()).map(|(l, r)| l.abs_diff(*r)).sum();
-    println!("{result}");
+    result.into()
 }
 
-pub fn day1_b() {
-    let (left, right) = get_values();
+pub fn day1_b((left, right): &(Vec<u32>, Vec<u32>)) -> Answer {
     let mut counts = HashMap::new();
     right.iter().fold(&mut counts, |h, i| {
         *h.entry(i).or_default() += 1;
         h
     });
-    let result: u32 = left.into_iter().map(|n| n * counts.get(&n).unwrap_or(&0)).sum();
-    println!("{result}");
+    let result: u32 = left.iter().map(|n| n * counts.get(n).unwrap_or(&0)).sum();
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("examples/day1.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day1_a(&get_values(EXAMPLE).unwrap()), Answer::from(11));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day1_b(&get_values(EXAMPLE).unwrap()), Answer::from(31));
+    }
 }
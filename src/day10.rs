@@ -1,49 +1,47 @@
-use std::collections::{HashMap, HashSet};
-use std::mem;
-use super::read_grid_digits;
-
-fn directions(x: usize, y: usize, mountain: &[Box<[u8]>]) -> [Option<(usize, usize)>; 4] {
-    [
-        (y > 0).then(|| (x, y - 1)),
-        (x + 1 < mountain[0].len()).then(|| (x + 1, y)),
-        (y + 1 < mountain.len()).then(|| (x, y + 1)),
-        (x > 0).then(|| (x - 1, y)),
-    ]
+use super::{Answer, Grid, ParseError, Search, Vec2, bfs, parse_grid_digits};
+
+pub fn get_mountain(input: &str) -> Result<Grid<u8>, ParseError> { parse_grid_digits(10, input) }
+
+fn trailheads(mountain: &Grid<u8>) -> impl Iterator<Item = Vec2<usize>> {
+    mountain.iter().filter(|&(_, &b)| b == 0).map(|(p, _)| p)
 }
 
-fn count<T: Default>(
-    init: impl Fn(usize, usize) -> T, sum: impl Fn(&mut T, &T), value: impl Fn(T) -> usize,
-) -> usize {
-    let mountain = read_grid_digits(10);
-    let mut positions = HashMap::new();
-    for (y, r) in mountain.iter().enumerate() {
-        for (x, b) in r.iter().enumerate() {
-            if *b == 0 {
-                positions.insert((x, y), init(x, y));
-            }
-        }
-    }
-    let mut positions_next = HashMap::new();
-    for next in 1..=9 {
-        for ((x, y), v) in positions {
-            for c in directions(x, y, &mountain) {
-                if let Some((x_next, y_next)) = c && mountain[y_next][x_next] == next {
-                    sum(positions_next.entry((x_next, y_next)).or_default(), &v);
-                }
-            }
-        }
-        positions = mem::take(&mut positions_next);
-    }
-    positions.into_values().map(value).sum()
+fn climb(
+    mountain: &Grid<u8>, starts: impl IntoIterator<Item = Vec2<usize>>,
+) -> Search<Vec2<usize>> {
+    bfs(starts, |p| mountain.neighbours(p).filter(move |&n| mountain[n] == mountain[p] + 1))
 }
 
-pub fn day10_b() {
-    #[expect(clippy::cast_sign_loss)]
-    let result = count(|_, _| 1, |v1, v2| *v1 += *v2, |v| v as _);
-    println!("{result}");
+fn peaks(mountain: &Grid<u8>) -> impl Iterator<Item = Vec2<usize>> {
+    mountain.iter().filter(|&(_, &b)| b == 9).map(|(p, _)| p)
 }
 
-pub fn day10_a() {
-    let result = count(|x, y| HashSet::from([50 * y + x]), |v1, v2| v1.extend(v2), |v| v.len());
-    println!("{result}");
+pub fn day10_b(mountain: &Grid<u8>) -> Answer {
+    let search = climb(mountain, trailheads(mountain));
+    let result: usize = peaks(mountain).map(|p| search.count_paths(p)).sum();
+    result.into()
+}
+
+pub fn day10_a(mountain: &Grid<u8>) -> Answer {
+    let result: usize = trailheads(mountain)
+        .map(|t| climb(mountain, [t]).distances().filter(|&(p, _)| mountain[p] == 9).count())
+        .sum();
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("examples/day10.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day10_a(&get_mountain(EXAMPLE).unwrap()), Answer::from(36));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day10_b(&get_mountain(EXAMPLE).unwrap()), Answer::from(81));
+    }
 }
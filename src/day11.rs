@@ -1,9 +1,12 @@
 use std::collections::HashMap;
-use super::read_numbers;
 
-fn count(limit: usize) -> usize {
-    let mut numbers: HashMap<u64, usize> = read_numbers(11).into_iter().map(|v| (v, 1)).collect();
-    for i in 0..limit {
+use super::{Answer, ParseError, parse_numbers};
+
+pub fn get_stones(input: &str) -> Result<Vec<u64>, ParseError> { parse_numbers(11, input) }
+
+pub fn count(stones: &[u64], limit: usize) -> usize {
+    let mut numbers: HashMap<u64, usize> = stones.iter().map(|&v| (v, 1)).collect();
+    for _ in 0..limit {
         let mut numbers_next = HashMap::new();
         let mut add = |v, c| *numbers_next.entry(v).or_default() += c;
         for (n, c) in numbers {
@@ -25,13 +28,29 @@ fn count(limit: usize) -> usize {
     numbers.into_values().sum()
 }
 
-pub fn day11_a() {
-    let result = count(25);
-    println!("{result}");
+pub fn day11_a(stones: &[u64]) -> Answer {
+    let result = count(stones, 25);
+    result.into()
+}
+
+pub fn day11_b(stones: &[u64]) -> Answer {
+    let result = count(stones, 75);
+    result.into()
 }
 
-pub fn day11_b() {
-    let result = count(75);
-    println!("{result}");
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    const EXAMPLE: &str = include_str!("examples/day11.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day11_a(&get_stones(EXAMPLE).unwrap()), Answer::from(55_312));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day11_b(&get_stones(EXAMPLE).unwrap()), Answer::from(65_601_038_650_482_i64));
+    }
 }
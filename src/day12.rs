@@ -1,114 +1,62 @@
 use std::collections::HashSet;
 
-use super::read_grid_bytes;
+use super::{Answer, Direction, Grid, ParseError, Vec2, parse_grid_bytes};
 
-#[derive(Eq, PartialEq, Hash, Copy, Clone)]
-struct Position {
-    x: usize,
-    y: usize,
-}
-
-impl Position {
-    const fn new(x: usize, y: usize) -> Self { Self { x, y } }
-
-    fn top(&self) -> Option<Self> { (self.y > 0).then(|| (Self { x: self.x, y: self.y - 1 })) }
-
-    fn right(&self, max: usize) -> Option<Self> {
-        (self.x + 1 < max).then(|| (Self { x: self.x + 1, y: self.y }))
-    }
-
-    fn bottom(&self, max: usize) -> Option<Self> {
-        (self.y + 1 < max).then(|| (Self { x: self.x, y: self.y + 1 }))
-    }
-
-    fn left(&self) -> Option<Self> { (self.x > 0).then(|| (Self { x: self.x - 1, y: self.y })) }
-
-    fn neighbours(&self, grid: &[Box<[u8]>]) -> [Option<(Self, u8)>; 4] {
-        [self.top(), self.right(grid[0].len()), self.bottom(grid.len()), self.left()]
-            .map(|o| o.map(|p| (p, grid[p.y][p.x])))
-    }
-
-    fn neighbours_corners(&self, grid: &[Box<[u8]>]) -> [[Option<(Self, u8)>; 3]; 3] {
-        let top = self.top();
-        let top_left = top.and_then(|p| p.left());
-        let top_right = top.and_then(|p| p.right(grid[0].len()));
-        let bottom = self.bottom(grid.len());
-        let bottom_left = bottom.and_then(|p| p.left());
-        let bottom_right = bottom.and_then(|p| p.right(grid[0].len()));
-        let left = self.left();
-        let right = self.right(grid[0].len());
-        [[top_left, top, top_right], [left, Some(*self), right], [
-            bottom_left, bottom, bottom_right,
-        ]]
-        .map(|a| a.map(|o| o.map(|p| (p, grid[p.y][p.x]))))
-    }
-
-    fn iter_positions(grid: &[Box<[u8]>]) -> impl Iterator<Item = (Self, u8)> {
-        grid.iter()
-            .enumerate()
-            .flat_map(|(y, r)| r.iter().enumerate().map(move |(x, &b)| (Self { x, y }, b)))
-    }
-}
+pub fn get_farm(input: &str) -> Result<Grid<u8>, ParseError> { parse_grid_bytes(12, input) }
 
 fn count(
-    f: impl Fn(Position, u8, &[Box<[u8]>], &mut HashSet<Position>) -> (usize, usize),
+    farm: &Grid<u8>,
+    f: impl Fn(Vec2<usize>, u8, &Grid<u8>, &mut HashSet<Vec2<usize>>) -> (usize, usize),
 ) -> usize {
-    let farm = read_grid_bytes(12);
-    let mut positions_used: HashSet<Position> = HashSet::new();
+    let mut positions_used = HashSet::new();
     let mut result = 0;
-    for (p, b) in Position::iter_positions(&farm) {
+    for (p, &b) in farm.iter() {
         if positions_used.insert(p) {
-            let (a, p) = f(p, b, &farm, &mut positions_used);
+            let (a, p) = f(p, b, farm, &mut positions_used);
             result += a * p;
         }
     }
     result
 }
 
-pub fn day12_a() {
-    let result = count(flood_a);
-    println!("{result}");
+pub fn day12_a(farm: &Grid<u8>) -> Answer {
+    let result = count(farm, flood_a);
+    result.into()
 }
 
-pub fn day12_b() {
-    let result = count(flood_b);
-    println!("{result}");
+pub fn day12_b(farm: &Grid<u8>) -> Answer {
+    let result = count(farm, flood_b);
+    result.into()
 }
 
 fn flood_a(
-    position: Position, plot: u8, farm: &[Box<[u8]>], used: &mut HashSet<Position>,
+    position: Vec2<usize>, plot: u8, farm: &Grid<u8>, used: &mut HashSet<Vec2<usize>>,
 ) -> (usize, usize) {
     let mut area = 1;
-    let mut perimeter = 0;
-    for o in position.neighbours(farm) {
-        match o {
-            Some((p, b)) if b == plot =>
-                if used.insert(p) {
-                    let (a, p) = flood_a(p, plot, farm, used);
-                    area += a;
-                    perimeter += p;
-                },
-            _ => perimeter += 1,
+    let mut perimeter = 4;
+    for p in farm.neighbours(position) {
+        if farm[p] == plot {
+            perimeter -= 1;
+            if used.insert(p) {
+                let (a, p) = flood_a(p, plot, farm, used);
+                area += a;
+                perimeter += p;
+            }
         }
     }
     (area, perimeter)
 }
 
 fn flood_b(
-    position: Position, plot: u8, farm: &[Box<[u8]>], used: &mut HashSet<Position>,
+    position: Vec2<usize>, plot: u8, farm: &Grid<u8>, used: &mut HashSet<Vec2<usize>>,
 ) -> (usize, usize) {
     used.insert(position);
     let mut area = 1;
     let mut sides = 0;
-    let [[top_left, top, top_right], [left, _, right], [bottom_left, bottom, bottom_right]] =
-        position.neighbours_corners(farm);
-    let checks = [
-        (top, left, top_left),
-        (right, top, top_right),
-        (bottom, right, bottom_right),
-        (left, bottom, bottom_left),
-    ];
-    for (main, neighbor, corner) in checks {
+    let at = |d| farm.step(position, d).map(|p| (p, farm[p]));
+    for d in Direction::CARDINAL {
+        let (main, neighbor, corner) =
+            (at(d), at(d.counterclockwise()), at(d.counterclockwise_eighth()));
         match main {
             Some((p, b)) if b == plot =>
                 if used.insert(p) {
@@ -125,3 +73,76 @@ fn flood_b(
     }
     (area, sides)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use super::*;
+    use crate::utils::{Rng, bfs};
+
+    const EXAMPLE: &str = include_str!("examples/day12.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day12_a(&get_farm(EXAMPLE).unwrap()), Answer::from(1930));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day12_b(&get_farm(EXAMPLE).unwrap()), Answer::from(1206));
+    }
+
+    /// Prices for both parts, counting a side as a run of adjacent fence pieces facing the same
+    /// way on the same row or column.
+    fn prices(farm: &Grid<u8>) -> (usize, usize) {
+        let mut seen = HashSet::new();
+        let (mut perimeter_price, mut side_price) = (0, 0);
+        for (start, &plot) in farm.iter() {
+            if seen.contains(&start) {
+                continue;
+            }
+            let region = bfs([start], |p| farm.neighbours(p).filter(|&n| farm[n] == plot));
+            let cells: Vec<_> = region.distances().map(|(p, _)| p).collect();
+            seen.extend(cells.iter().copied());
+            let mut fences = BTreeMap::<_, BTreeSet<_>>::new();
+            for &p in &cells {
+                for d in Direction::CARDINAL {
+                    if farm.step(p, d).is_none_or(|n| farm[n] != plot) {
+                        let (line, along) = if d.offset().y == 0 { (p.x, p.y) } else { (p.y, p.x) };
+                        fences.entry((d.index(), line)).or_default().insert(along);
+                    }
+                }
+            }
+            let perimeter: usize = fences.values().map(BTreeSet::len).sum();
+            let sides: usize = fences
+                .values()
+                .map(|run| run.iter().filter(|&&a| a == 0 || !run.contains(&(a - 1))).count())
+                .sum();
+            perimeter_price += cells.len() * perimeter;
+            side_price += cells.len() * sides;
+        }
+        (perimeter_price, side_price)
+    }
+
+    /// A farm of up to 8 by 8 plots from a small alphabet, so regions nest and touch diagonally.
+    fn farm(rng: &mut Rng) -> String {
+        let (width, height) = (rng.range(1..=8), rng.range(1..=8));
+        let plants = &b"ABC"[..rng.range(1..=3)];
+        (0..height)
+            .map(|_| (0..width).map(|_| char::from(*rng.pick(plants))).collect::<String>() + "\n")
+            .collect()
+    }
+
+    #[test]
+    fn prices_match_fence_runs() {
+        let mut rng = Rng::new(12);
+        for _ in 0..500 {
+            let input = farm(&mut rng);
+            let farm = get_farm(&input).unwrap();
+            let (perimeter, sides) = prices(&farm);
+            assert_eq!(day12_a(&farm), Answer::from(perimeter), "{input}");
+            assert_eq!(day12_b(&farm), Answer::from(sides), "{input}");
+        }
+    }
+}
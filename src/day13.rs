@@ -1,6 +1,9 @@
-use super::{IntDivide as _, SplitOnceArr as _, Vec2, read_lines};
+use std::cmp::Ordering;
 
-struct Machine {
+use super::{Answer, IntDivide as _, Line, Lines, ParseError, Vec2};
+
+#[derive(Copy, Clone)]
+pub struct Machine {
     a: Vec2<i64>,
     b: Vec2<i64>,
     prize: Vec2<i64>,
@@ -11,20 +14,25 @@ impl From<[Vec2<i64>; 3]> for Machine {
 }
 
 impl Machine {
-    fn parse(lines: &[String; 3]) -> Self {
-        Self::from(lines.each_ref().map(|l| {
-            Vec2::<i64>::from(
-                l.split_once(':')
-                    .unwrap()
-                    .1
-                    .split_once_arr(',')
-                    .unwrap()
-                    .map(|v| v.split_once(['+', '=']).unwrap().1.parse().unwrap()),
-            )
-        }))
+    fn parse(lines: [Line; 3]) -> Result<Self, ParseError> {
+        let [a, b, prize] = lines;
+        Ok(Self::from([Self::parse_line(a)?, Self::parse_line(b)?, Self::parse_line(prize)?]))
+    }
+
+    fn parse_line(line: Line) -> Result<Vec2<i64>, ParseError> {
+        let [_, values] = line.split_once(line.text, ':', "`:`")?;
+        let [x, y] = line.split_once(values, ',', "`,`")?;
+        let value = |s| {
+            let [_, v] = line.split_once(s, ['+', '='], "`+` or `=`")?;
+            line.parse(v, "a number")
+        };
+        Ok(Vec2::new(value(x)?, value(y)?))
     }
 
     fn count(self) -> Option<i64> {
+        if self.a.cross_product(self.b) == 0 {
+            return self.count_collinear();
+        }
         // p_x - n * a_x = m * b_x => m = (p_x - n * a_x) / b_x
         // p_y - n * a_y = m * b_y => m = (p_y - n * a_y) / b_y
         // (p_x - n * a_x) / b_x = (p_y - n * a_y) / b_y
@@ -33,26 +41,173 @@ impl Machine {
         // n * (a_y * b_x - a_x * b_y) = b_x * p_y - b_y * p_x
         // n = (b_x * p_y - b_y * p_x) / (a_y * b_x - a_x * b_y)
         let n = self.b.cross_product(self.prize).int_divide(self.b.cross_product(self.a))?;
-        let m = (self.prize.x - n * self.a.x).int_divide(self.b.x)?;
-        Some(3 * n + m)
+        // B is not parallel to A, so it moves along at least one axis.
+        let m = if self.b.x == 0 {
+            (self.prize.y - n * self.a.y).int_divide(self.b.y)?
+        } else {
+            (self.prize.x - n * self.a.x).int_divide(self.b.x)?
+        };
+        // The exact solution can ask for a negative number of presses, which no machine allows.
+        (n >= 0 && m >= 0).then_some(3 * n + m)
+    }
+
+    /// The cheapest presses when both buttons move along the same line, so that many
+    /// combinations may reach the prize.
+    fn count_collinear(self) -> Option<i64> {
+        let Self { a, b, prize } = self;
+        if a.cross_product(prize) != 0 || b.cross_product(prize) != 0 {
+            return None;
+        }
+        // Everything lies on one line, so an axis along it holds the whole equation.
+        let axis = |v: Vec2<i64>| i128::from(if a.x != 0 || b.x != 0 { v.x } else { v.y });
+        let [step_a, step_b, target] = [a, b, prize].map(axis);
+        if step_a == 0 && step_b == 0 {
+            return (target == 0).then_some(0);
+        }
+        let (gcd, factor_a, factor_b) = extended_gcd(step_a, step_b);
+        if target % gcd != 0 {
+            return None;
+        }
+        // The solutions are n = n0 + k * dn and m = m0 + k * dm for any k.
+        let (n0, m0) = (factor_a * (target / gcd), factor_b * (target / gcd));
+        let (dn, dm) = (step_b / gcd, -step_a / gcd);
+        let (mut low, mut high) = (i128::MIN, i128::MAX);
+        for (start, step) in [(n0, dn), (m0, dm)] {
+            match step.cmp(&0) {
+                Ordering::Greater => low = low.max(-start.div_euclid(step)),
+                Ordering::Less => high = high.min(start.div_euclid(-step)),
+                Ordering::Equal if start < 0 => return None,
+                Ordering::Equal => {},
+            }
+        }
+        if low > high {
+            return None;
+        }
+        // The cost changes by the same amount for each step of k, and the presses bound k on
+        // the side where the cost falls.
+        let k = if 3 * dn + dm > 0 || high == i128::MAX { low } else { high };
+        i64::try_from(3 * (n0 + k * dn) + m0 + k * dm).ok()
+    }
+}
+
+/// `(g, x, y)` with `g` the positive greatest common divisor of `a` and `b`, not both zero,
+/// and `a * x + b * y = g`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        return (a.abs(), a.signum(), 0);
     }
+    let (gcd, factor_b, factor_rem) = extended_gcd(b, a.rem_euclid(b));
+    (gcd, factor_rem, factor_b - a.div_euclid(b) * factor_rem)
 }
 
-fn read_machines() -> impl Iterator<Item = Machine> {
-    read_lines(13).filter(|s| !s.is_empty()).array_chunks().map(|m| Machine::parse(&m))
+pub fn read_machines(input: &str) -> Result<Vec<Machine>, ParseError> {
+    let mut lines = Lines::new(13, input);
+    let mut machines = Vec::new();
+    while let Some(a) = lines.find(|l| !l.text.is_empty()) {
+        let [b, prize] = [lines.expect("a `Button B` line")?, lines.expect("a `Prize` line")?];
+        machines.push(Machine::parse([a, b, prize])?);
+    }
+    Ok(machines)
 }
 
-pub fn day13_a() {
-    let result: i64 = read_machines().filter_map(Machine::count).sum();
-    println!("{result}");
+pub fn day13_a(machines: &[Machine]) -> Answer {
+    let result: i64 = machines.iter().copied().filter_map(Machine::count).sum();
+    result.into()
 }
 
-pub fn day13_b() {
-    let result: i64 = read_machines()
+pub fn day13_b(machines: &[Machine]) -> Answer {
+    let result: i64 = machines
+        .iter()
+        .copied()
         .filter_map(|mut m| {
             m.prize += 10_000_000_000_000;
             m.count()
         })
         .sum();
-    println!("{result}");
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write as _;
+
+    use super::*;
+    use crate::utils::Rng;
+
+    const EXAMPLE: &str = include_str!("examples/day13.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day13_a(&read_machines(EXAMPLE).unwrap()), Answer::from(480));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day13_b(&read_machines(EXAMPLE).unwrap()), Answer::from(875_318_608_908_i64));
+    }
+
+    /// Tries every count of A presses that does not overshoot the prize.
+    fn cheapest(machine: Machine) -> Option<i64> {
+        let Machine { a, b, prize } = machine;
+        (0..=prize.x / a.x)
+            .filter_map(|n| {
+                let (x, y) = (prize.x - n * a.x, prize.y - n * a.y);
+                let m = x / b.x;
+                (x % b.x == 0 && y == m * b.y).then_some(3 * n + m)
+            })
+            .min()
+    }
+
+    /// Machines with buttons that are parallel a quarter of the time, half of them with a prize
+    /// reachable in at most 100 presses of each button and half with an arbitrary prize.
+    fn machines(rng: &mut Rng) -> String {
+        let mut input = String::new();
+        for _ in 0..rng.range(1..=5) {
+            let mut number = |range| i64::try_from(rng.range(range)).unwrap();
+            let (a, b) = if number(0..=3) == 0 {
+                let line = Vec2::new(number(1..=9), number(1..=9));
+                let (n, m) = (number(1..=9), number(1..=9));
+                (Vec2::new(n * line.x, n * line.y), Vec2::new(m * line.x, m * line.y))
+            } else {
+                (
+                    Vec2::new(number(1..=99), number(1..=99)),
+                    Vec2::new(number(1..=99), number(1..=99)),
+                )
+            };
+            let prize = if number(0..=1) == 0 {
+                let (n, m) = (number(0..=100), number(0..=100));
+                Vec2::new(n * a.x + m * b.x, n * a.y + m * b.y)
+            } else {
+                Vec2::new(number(0..=20_000), number(0..=20_000))
+            };
+            write!(
+                input,
+                "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n\n",
+                a.x, a.y, b.x, b.y, prize.x, prize.y
+            )
+            .unwrap();
+        }
+        input
+    }
+
+    #[test]
+    fn part_a_matches_trying_every_count() {
+        let mut rng = Rng::new(13);
+        for _ in 0..500 {
+            let input = machines(&mut rng);
+            let machines = read_machines(&input).unwrap();
+            let expected: i64 = machines.iter().copied().filter_map(cheapest).sum();
+            assert_eq!(day13_a(&machines), Answer::from(expected), "{input}");
+        }
+    }
+
+    #[test]
+    fn buttons_along_one_axis() {
+        let input = "Button A: X+0, Y+5\nButton B: X+3, Y+0\nPrize: X=6, Y=10\n\n\
+                     Button A: X+3, Y+1\nButton B: X+0, Y+2\nPrize: X=6, Y=6\n\n\
+                     Button A: X+0, Y+2\nButton B: X+0, Y+3\nPrize: X=0, Y=7";
+        let machines = read_machines(input).unwrap();
+        let counts: Vec<_> = machines.iter().map(|m| m.count()).collect();
+        assert_eq!(counts, [Some(8), Some(8), Some(7)]);
+    }
 }
@@ -1,9 +1,9 @@
 use std::cmp::Ordering;
-use std::io;
 
-use super::{SplitOnceArr as _, Vec2, read_lines};
+use super::{Answer, Line, Lines, ParseError, SolveError, Vec2};
 
-struct Robot {
+#[derive(Copy, Clone)]
+pub struct Robot {
     position: Vec2<i64>,
     velocity: Vec2<i64>,
 }
@@ -11,30 +11,30 @@ struct Robot {
 impl Robot {
     const AREA_SIZE: Vec2<i64> = Vec2 { x: 101, y: 103 };
 
-    // const AREA_SIZE: Vec2<i64> = Vec2 { x: 11, y: 7 };
-
-    fn parse(line: &str) -> Self {
-        line.split_once_arr(' ')
-            .unwrap()
-            .map(|s| {
-                s.split_once('=')
-                    .unwrap()
-                    .1
-                    .split_once_arr(',')
-                    .unwrap()
-                    .map(|s| s.parse().unwrap())
-                    .into()
-            })
-            .into()
+    fn parse(line: Line) -> Result<Self, ParseError> {
+        let [position, velocity] = line.split_once(line.text, ' ', "` `")?;
+        let vector = |s| -> Result<[&str; 2], ParseError> {
+            let [_, v] = line.split_once(s, '=', "`=`")?;
+            line.split_once(v, ',', "`,`")
+        };
+        let within = |s, size: i64| {
+            let n = line.parse(s, "a number")?;
+            let expected = || format!("a number from 0 to {}", size - 1);
+            if (0..size).contains(&n) { Ok(n) } else { Err(line.error(s, expected())) }
+        };
+        let ([px, py], [vx, vy]) = (vector(position)?, vector(velocity)?);
+        let position = Vec2::new(within(px, Self::AREA_SIZE.x)?, within(py, Self::AREA_SIZE.y)?);
+        let velocity = Vec2::new(line.parse(vx, "a number")?, line.parse(vy, "a number")?);
+        Ok(Self::from([position, velocity]))
     }
 
-    fn step(&mut self, steps: i64) {
+    fn step(&mut self, steps: i64, area: Vec2<i64>) {
         self.position += self.velocity * steps;
-        self.position = self.position.rem_euclid(Self::AREA_SIZE);
+        self.position = self.position.rem_euclid(area);
     }
 
-    fn quadrant(&self) -> Option<usize> {
-        let limit = Self::AREA_SIZE / 2;
+    fn quadrant(&self, area: Vec2<i64>) -> Option<usize> {
+        let limit = area / 2;
         let cmp: [_; 2] = self.position.cmp_each(&limit).into();
         match cmp {
             [_, Ordering::Equal] | [Ordering::Equal, _] => None,
@@ -46,7 +46,7 @@ impl Robot {
     }
 
     #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-    fn display_robots(robots: &[Self], min_neighbours: usize) -> bool {
+    fn count_neighbours(robots: &[Self]) -> usize {
         let mut grid = [[b'.'; Self::AREA_SIZE.y as _]; Self::AREA_SIZE.x as _];
         for r in robots {
             match &mut grid[r.position.x as usize][r.position.y as usize] {
@@ -67,14 +67,7 @@ impl Robot {
                 }
             }
         }
-        if neighbours >= min_neighbours {
-            let v: Vec<_> = grid.iter().map(|b| std::str::from_utf8(b).unwrap()).collect();
-            let content = v.join("\n");
-            println!("{content}");
-            true
-        } else {
-            false
-        }
+        neighbours
     }
 }
 
@@ -82,34 +75,61 @@ impl From<[Vec2<i64>; 2]> for Robot {
     fn from([position, velocity]: [Vec2<i64>; 2]) -> Self { Self { position, velocity } }
 }
 
-fn get_robots() -> impl Iterator<Item = Robot> { read_lines(14).map(|s| Robot::parse(&s)) }
+pub fn get_robots(input: &str) -> Result<Vec<Robot>, ParseError> {
+    Lines::new(14, input).map(Robot::parse).collect()
+}
 
-pub fn day14_a() {
+pub fn safety_factor(robots: &[Robot], area: Vec2<i64>) -> i32 {
     let mut quadrant_counts = [0; 4];
-    get_robots()
+    robots
+        .iter()
+        .copied()
         .filter_map(|mut r| {
-            r.step(100);
-            r.quadrant()
+            r.step(100, area);
+            r.quadrant(area)
         })
         .for_each(|q| quadrant_counts[q] += 1);
-    let result: i32 = quadrant_counts.into_iter().product();
-    println!("{result}");
+    quadrant_counts.into_iter().product()
+}
+
+pub fn day14_a(robots: &[Robot]) -> Answer {
+    let result = safety_factor(robots, Robot::AREA_SIZE);
+    result.into()
 }
 
-pub fn day14_b() {
-    let mut robots: Vec<_> = get_robots().collect();
-    let mut step = 0;
-    let stdin = io::stdin();
-    let mut content = String::new();
-    loop {
-        if Robot::display_robots(&robots, 500) {
-            println!("Step: {step}");
-            stdin.read_line(&mut content).unwrap();
+/// The first second at which the robots clump together into a picture. The robots are back
+/// where they started after `101 * 103` seconds, so the search stops there.
+pub fn day14_b(robots: &[Robot]) -> Result<Answer, SolveError> {
+    let mut robots = robots.to_vec();
+    let period = Robot::AREA_SIZE.x * Robot::AREA_SIZE.y;
+    for step in 0..period {
+        if Robot::count_neighbours(&robots) >= 500 {
+            return Ok(Answer::from(step));
         }
-        if !content.trim().is_empty() {
-            break;
+        for r in &mut robots {
+            r.step(1, Robot::AREA_SIZE);
         }
-        robots.iter_mut().for_each(|r| r.step(1));
-        step += 1;
+    }
+    Err(SolveError::new(14, "the robots never clump together"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("examples/day14.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(safety_factor(&get_robots(EXAMPLE).unwrap(), Vec2::new(11, 7)), 12);
+    }
+
+    #[test]
+    fn part_b() {
+        assert!(day14_b(&get_robots(EXAMPLE).unwrap()).is_err());
+        assert_eq!(
+            get_robots("p=200,5 v=1,1").err().unwrap().to_string(),
+            "day 14 input, line 1, column 3: expected a number from 0 to 100"
+        );
     }
 }
@@ -1,82 +1,72 @@
 use std::collections::HashMap;
 
-use super::{Vec2, read_lines};
-
-#[derive(Copy, Clone, Eq, PartialEq)]
-enum Move {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
-impl Move {
-    fn parse(b: u8) -> Self {
-        match b {
-            b'^' => Self::Up,
-            b'>' => Self::Right,
-            b'v' => Self::Down,
-            b'<' => Self::Left,
-            _ => unreachable!(),
+use super::{Answer, Direction, Grid, Lines, ParseError, Vec2, find_cell};
+
+pub fn get_data(input: &str) -> Result<(Grid<u8>, Vec<Direction>), ParseError> {
+    let mut lines = Lines::new(15, input);
+    let mut rows = Vec::new();
+    for l in lines.by_ref().take_while(|l| !l.text.is_empty()) {
+        if let Some(i) = l.text.bytes().position(|b| !b"#.O@".contains(&b)) {
+            return Err(l.error(&l.text[i..], "one of `#.O@`"));
+        }
+        if let Some(width) = rows.first().map(|r: &&str| r.len())
+            && width != l.text.len()
+        {
+            let column = l.text.len().min(width) + 1;
+            return Err(ParseError::new(15, l.number, column, format!("a row of {width} cells")));
         }
+        rows.push(l.text);
     }
-
-    const fn offset(self, mut vec: Vec2<usize>) -> Vec2<usize> {
-        match self {
-            Self::Up => vec.y -= 1,
-            Self::Right => vec.x += 1,
-            Self::Down => vec.y += 1,
-            Self::Left => vec.x -= 1,
+    let grid = Grid::from_rows(rows.iter().map(|r| r.bytes()));
+    let (width, height) = (grid.width(), grid.height());
+    let edge = |p: Vec2<usize>| p.x == 0 || p.y == 0 || p.x == width - 1 || p.y == height - 1;
+    if let Some(p) = grid.positions().find(|&p| edge(p) && grid[p] != b'#') {
+        return Err(ParseError::new(15, p.y + 1, p.x + 1, "a `#` wall around the warehouse"));
+    }
+    find_cell(15, &grid, b'@', "a `@` cell")?;
+    let mut moves = Vec::new();
+    for l in lines {
+        for (i, b) in l.text.bytes().enumerate() {
+            let m =
+                Direction::from_arrow(b).ok_or_else(|| l.error(&l.text[i..], "one of `^>v<`"))?;
+            moves.push(m);
         }
-        vec
     }
-}
-
-fn get_data(thick: bool) -> (World, impl Iterator<Item = Move>) {
-    let mut lines = read_lines(15);
-    let world = if thick { World::new_thick(&mut lines) } else { World::new_thin(&mut lines) };
-    let moves = lines.flat_map(|s| s.into_bytes().into_iter().map(Move::parse));
-    (world, moves)
+    Ok((grid, moves))
 }
 
 struct World {
-    grid: Box<[Box<[u8]>]>,
+    grid: Grid<u8>,
     robot: Vec2<usize>,
 }
 
 impl World {
-    fn new(lines: &mut impl Iterator<Item = String>, row: impl Fn(String) -> Box<[u8]>) -> Self {
-        let mut grid: Box<[Box<[u8]>]> =
-            lines.by_ref().take_while(|s| !s.is_empty()).map(row).collect();
-        let robot = grid
-            .iter()
-            .enumerate()
-            .find_map(|(y, r)| r.iter().position(|&b| b == b'@').map(|x| Vec2 { x, y }))
-            .unwrap();
-        grid[robot.y][robot.x] = b'.';
+    fn new(mut grid: Grid<u8>) -> Self {
+        let robot = grid.find(&b'@').unwrap();
+        grid[robot] = b'.';
         Self { grid, robot }
     }
 
-    fn new_thin(lines: &mut impl Iterator<Item = String>) -> Self {
-        Self::new(lines, |s| s.into_boxed_str().into_boxed_bytes())
+    fn new_thin(grid: &Grid<u8>) -> Self { Self::new(grid.clone()) }
+
+    fn new_thick(grid: &Grid<u8>) -> Self {
+        Self::new(Grid::from_rows(grid.rows().map(|r| {
+            r.iter().flat_map(|&b| match b {
+                b'@' => *b"@.",
+                b'O' => *b"[]",
+                b => [b, b],
+            })
+        })))
     }
 
-    fn new_thick(lines: &mut impl Iterator<Item = String>) -> Self {
-        Self::new(lines, |s| {
-            s.bytes()
-                .flat_map(|b| match b {
-                    b'@' => [b'@', b'.'],
-                    b'O' => [b'[', b']'],
-                    b => [b, b],
-                })
-                .collect()
-        })
+    const fn next(&self, position: Vec2<usize>, m: Direction) -> Vec2<usize> {
+        self.grid.step(position, m).expect("the parser checks the walls around the warehouse")
     }
 
-    fn simulate_thin(&mut self, moves: impl Iterator<Item = Move>) {
+    fn simulate_thin(&mut self, moves: impl Iterator<Item = Direction>) {
         for m in moves {
-            let first = m.offset(self.robot);
-            match self.grid[first.y][first.x] {
+            let first = self.next(self.robot, m);
+            match self.grid[first] {
                 b'.' => self.robot = first,
                 b'O' => self.push_thin(m, first),
                 b'#' => {},
@@ -85,14 +75,14 @@ impl World {
         }
     }
 
-    fn simulate_thick(&mut self, moves: impl Iterator<Item = Move>) {
+    fn simulate_thick(&mut self, moves: impl Iterator<Item = Direction>) {
         for m in moves {
-            let first = m.offset(self.robot);
-            match self.grid[first.y][first.x] {
+            let first = self.next(self.robot, m);
+            match self.grid[first] {
                 b'.' => self.robot = first,
                 b @ (b'[' | b']') => match m {
-                    Move::Left | Move::Right => self.push_thick_horizontal(m, first),
-                    Move::Up | Move::Down => self.push_thick_vertical(m, first, b),
+                    Direction::West | Direction::East => self.push_thick_horizontal(m, first),
+                    _ => self.push_thick_vertical(m, first, b),
                 },
                 b'#' => {},
                 _ => unreachable!(),
@@ -100,56 +90,56 @@ impl World {
         }
     }
 
-    fn push_thin(&mut self, m: Move, first: Vec2<usize>) {
+    fn push_thin(&mut self, m: Direction, first: Vec2<usize>) {
         let mut last = first;
         loop {
-            last = m.offset(last);
-            match self.grid[last.y][last.x] {
+            last = self.next(last, m);
+            match self.grid[last] {
                 b'.' => {
-                    self.grid[last.y][last.x] = b'O';
-                    self.grid[first.y][first.x] = b'.';
+                    self.grid[last] = b'O';
+                    self.grid[first] = b'.';
                     self.robot = first;
                     break;
                 },
                 b'#' => break,
-                b'O' => continue,
+                b'O' => {},
                 _ => unreachable!(),
             }
         }
     }
 
-    fn push_thick_horizontal(&mut self, m: Move, first: Vec2<usize>) {
+    fn push_thick_horizontal(&mut self, m: Direction, first: Vec2<usize>) {
         let mut last = first;
         loop {
-            last = m.offset(m.offset(last));
-            match self.grid[last.y][last.x] {
+            last = self.next(self.next(last, m), m);
+            match self.grid[last] {
                 b'.' => {
-                    if m == Move::Left {
-                        self.grid[first.y][last.x..=first.x].rotate_left(1);
+                    if m == Direction::West {
+                        self.grid.row_mut(first.y)[last.x..=first.x].rotate_left(1);
                     } else {
-                        self.grid[first.y][first.x..=last.x].rotate_right(1);
+                        self.grid.row_mut(first.y)[first.x..=last.x].rotate_right(1);
                     }
                     self.robot = first;
                     break;
                 },
-                b'[' | b']' => continue,
+                b'[' | b']' => {},
                 b'#' => break,
                 _ => unreachable!(),
             }
         }
     }
 
-    fn push_thick_vertical(&mut self, m: Move, first: Vec2<usize>, b: u8) {
+    fn push_thick_vertical(&mut self, m: Direction, first: Vec2<usize>, b: u8) {
         let mut pushes = Vec::new();
         let mut current = first;
         let other = if b == b'[' { first.x + 1 } else { first.x - 1 };
         let mut potential = HashMap::from([(first.x, first.y), (other, first.y)]);
         while !potential.is_empty() {
-            current = m.offset(current);
+            current = self.next(current, m);
             let mut to_delete = Vec::new();
             let mut to_add = Vec::new();
             for (&column, &start) in &potential {
-                match self.grid[current.y][column] {
+                match self.grid[Vec2 { x: column, y: current.y }] {
                     b'.' => {
                         pushes.push((column, start, current.y));
                         to_delete.push(column);
@@ -171,44 +161,65 @@ impl World {
                 potential.insert(c, current.y);
             }
         }
-        pushes.into_iter().for_each(|(x, s, e)| self.move_vertical(x, s, e, m));
+        for (x, s, e) in pushes {
+            self.move_vertical(x, s, e, m);
+        }
         self.robot = first;
     }
 
-    fn move_vertical(&mut self, x: usize, y_start: usize, y_end: usize, m: Move) {
-        if m == Move::Up {
+    fn move_vertical(&mut self, x: usize, y_start: usize, y_end: usize, m: Direction) {
+        if m == Direction::North {
             for y in y_end..y_start {
-                self.grid[y][x] = self.grid[y + 1][x];
+                self.grid[Vec2 { x, y }] = self.grid[Vec2 { x, y: y + 1 }];
             }
         } else {
             for y in (y_start + 1..=y_end).rev() {
-                self.grid[y][x] = self.grid[y - 1][x];
+                self.grid[Vec2 { x, y }] = self.grid[Vec2 { x, y: y - 1 }];
             }
         }
-        self.grid[y_start][x] = b'.';
+        self.grid[Vec2 { x, y: y_start }] = b'.';
     }
 
-    fn count(self, b: u8) -> usize {
-        self.grid
-            .into_iter()
-            .enumerate()
-            .flat_map(|(y, r)| {
-                r.into_iter().enumerate().filter(|&(_, c)| c == b).map(move |(x, _)| 100 * y + x)
-            })
-            .sum()
+    fn count(&self, b: u8) -> usize {
+        self.grid.iter().filter(|&(_, &c)| c == b).map(|(p, _)| 100 * p.y + p.x).sum()
     }
 }
 
-pub fn day15_a() {
-    let (mut world, moves) = get_data(false);
-    world.simulate_thin(moves);
+pub fn day15_a((grid, moves): &(Grid<u8>, Vec<Direction>)) -> Answer {
+    let mut world = World::new_thin(grid);
+    world.simulate_thin(moves.iter().copied());
     let result = world.count(b'O');
-    println!("{result}");
+    result.into()
 }
 
-pub fn day15_b() {
-    let (mut world, moves) = get_data(true);
-    world.simulate_thick(moves);
+pub fn day15_b((grid, moves): &(Grid<u8>, Vec<Direction>)) -> Answer {
+    let mut world = World::new_thick(grid);
+    world.simulate_thick(moves.iter().copied());
     let result = world.count(b'[');
-    println!("{result}");
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("examples/day15.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day15_a(&get_data(EXAMPLE).unwrap()), Answer::from(10_092));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day15_b(&get_data(EXAMPLE).unwrap()), Answer::from(9021));
+    }
+
+    #[test]
+    fn needs_walls() {
+        assert_eq!(
+            get_data("###\n#@.\n###\n\n>").unwrap_err().to_string(),
+            "day 15 input, line 2, column 3: expected a `#` wall around the warehouse"
+        );
+    }
 }
@@ -1,9 +1,14 @@
-use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
 
-use super::read_lines;
+use super::{Answer, Line, Lines, ParseError, SolveError};
 
-#[derive(Debug)]
-struct Computer {
+pub mod asm;
+pub mod compile;
+pub mod debug;
+
+#[derive(Clone, Debug)]
+pub struct Computer {
     reg_a: i64,
     reg_b: i64,
     reg_c: i64,
@@ -12,35 +17,51 @@ struct Computer {
     result: Vec<u8>,
 }
 
+/// Why a program stopped before halting.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Fault {
+    /// Combo operand 7 is reserved and does not appear in valid programs.
+    ReservedOperand(usize),
+    /// The program was still running after the given number of instructions.
+    StepLimit(usize),
+}
+
+impl Display for Fault {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::ReservedOperand(p) => write!(f, "reserved combo operand 7 at {p}"),
+            Self::StepLimit(n) => write!(f, "still running after {n} instructions"),
+        }
+    }
+}
+
+impl Error for Fault {}
+
 impl Computer {
     fn get_literal(&self) -> i64 { self.instructions[self.pointer + 1].into() }
 
-    fn get_combo(&self) -> i64 {
-        #[expect(clippy::match_on_vec_items)]
+    fn get_combo(&self) -> Result<i64, Fault> {
         match self.instructions[self.pointer + 1] {
-            o @ 0..=3 => o.into(),
-            4 => self.reg_a,
-            5 => self.reg_b,
-            6 => self.reg_c,
-            7 => todo!(),
+            o @ 0..=3 => Ok(o.into()),
+            4 => Ok(self.reg_a),
+            5 => Ok(self.reg_b),
+            6 => Ok(self.reg_c),
+            7 => Err(Fault::ReservedOperand(self.pointer)),
             _ => unreachable!(),
         }
     }
 
-    fn divide(&self) -> i64 {
-        let numerator = self.reg_a;
-        let power = self.get_combo();
-        if power > 64 {
-            0
-        } else {
-            let denominator = 1 << power;
-            numerator / denominator
-        }
+    /// `A / 2^power`. Registers never go negative, as parsing rejects negative values and no
+    /// instruction makes one, so the division is a shift.
+    fn divide(&self) -> Result<i64, Fault> {
+        let power = u32::try_from(self.get_combo()?).unwrap_or(u32::MAX);
+        Ok(self.reg_a.checked_shr(power).unwrap_or(0))
     }
 
-    fn adv(&mut self) {
-        self.reg_a = self.divide();
+    fn adv(&mut self) -> Result<(), Fault> {
+        self.reg_a = self.divide()?;
         self.pointer += 2;
+        Ok(())
     }
 
     fn bxl(&mut self) {
@@ -50,9 +71,10 @@ impl Computer {
         self.pointer += 2;
     }
 
-    fn bst(&mut self) {
-        self.reg_b = self.get_combo() & 0b111;
+    fn bst(&mut self) -> Result<(), Fault> {
+        self.reg_b = self.get_combo()? & 0b111;
         self.pointer += 2;
+        Ok(())
     }
 
     fn jnz(&mut self) {
@@ -64,46 +86,62 @@ impl Computer {
         }
     }
 
-    fn bxc(&mut self) {
+    const fn bxc(&mut self) {
         self.reg_b ^= self.reg_c;
         self.pointer += 2;
     }
 
-    fn out(&mut self) -> bool {
+    fn out(&mut self) -> Result<(), Fault> {
         #[expect(clippy::cast_sign_loss)]
-        self.result.push((self.get_combo() & 0b111) as u8);
+        self.result.push((self.get_combo()? & 0b111) as u8);
         self.pointer += 2;
-        self.result == self.instructions[0..self.result.len()]
+        Ok(())
     }
 
-    fn bdv(&mut self) {
-        self.reg_b = self.divide();
+    fn bdv(&mut self) -> Result<(), Fault> {
+        self.reg_b = self.divide()?;
         self.pointer += 2;
+        Ok(())
     }
 
-    fn cdv(&mut self) {
-        self.reg_c = self.divide();
+    fn cdv(&mut self) -> Result<(), Fault> {
+        self.reg_c = self.divide()?;
         self.pointer += 2;
+        Ok(())
+    }
+
+    /// Executes the instruction at the pointer. Returns `false` instead if the program has
+    /// halted, which it does once the pointer leaves the program.
+    pub fn step(&mut self) -> Result<bool, Fault> {
+        let Some(&[opcode, _]) = self.instructions.get(self.pointer..self.pointer + 2) else {
+            return Ok(false);
+        };
+        match opcode {
+            0 => self.adv()?,
+            1 => self.bxl(),
+            2 => self.bst()?,
+            3 => self.jnz(),
+            4 => self.bxc(),
+            5 => self.out()?,
+            6 => self.bdv()?,
+            7 => self.cdv()?,
+            _ => unreachable!(),
+        }
+        Ok(true)
     }
 
-    fn run(&mut self, finish: bool) -> bool {
-        while let Some(&i) = self.instructions.get(self.pointer) {
-            match i {
-                0 => self.adv(),
-                1 => self.bxl(),
-                2 => self.bst(),
-                3 => self.jnz(),
-                4 => self.bxc(),
-                5 =>
-                    if !self.out() && !finish {
-                        return false;
-                    },
-                6 => self.bdv(),
-                7 => self.cdv(),
-                _ => unreachable!(),
+    /// Runs the program for at most `limit` instructions and returns whether it output itself.
+    /// Unless `finish` is set, stops as soon as the output stops matching.
+    fn run(&mut self, finish: bool, limit: usize) -> Result<bool, Fault> {
+        for _ in 0..limit {
+            if !self.step()? {
+                return Ok(self.instructions == self.result);
+            }
+            if !finish && !self.instructions.starts_with(&self.result) {
+                return Ok(false);
             }
         }
-        self.instructions == self.result
+        Err(Fault::StepLimit(limit))
     }
 
     fn get_str(&self) -> String {
@@ -111,90 +149,208 @@ impl Computer {
         strings.join(",")
     }
 
-    fn parse(mut lines: impl Iterator<Item = String>) -> Self {
-        let [reg_a, reg_b, reg_c] = lines
-            .by_ref()
-            .take(3)
-            .map(|s| s.split_once(':').unwrap().1.trim().parse().unwrap())
-            .array_chunks()
-            .next()
-            .unwrap();
-        let instructions = lines
-            .nth(1)
-            .unwrap()
-            .split_once(':')
-            .unwrap()
-            .1
-            .trim()
+    pub fn parse(input: &str) -> Result<Self, ParseError> { Self::parse_checked(input, |_| true) }
+
+    /// Parses a program that part B can search, see [`Self::is_shift_loop`].
+    pub fn parse_shift_loop(input: &str) -> Result<Self, ParseError> {
+        Self::parse_checked(input, Self::is_shift_loop)
+    }
+
+    fn parse_checked(input: &str, valid: impl Fn(&Self) -> bool) -> Result<Self, ParseError> {
+        let mut lines = Lines::new(17, input);
+        let mut register = |name| {
+            let line = lines.expect(name)?;
+            let value = line.strip_prefix(line.text, name)?;
+            match line.parse(value, "a number")? {
+                register @ 0.. => Ok(register),
+                _ => Err(line.error(value, "a number of at least 0")),
+            }
+        };
+        let [reg_a, reg_b, reg_c] =
+            [register("Register A: ")?, register("Register B: ")?, register("Register C: ")?];
+        lines.expect_empty()?;
+        let line = lines.expect("`Program: `")?;
+        let program = line.strip_prefix(line.text, "Program: ")?;
+        let instructions: Vec<_> = program
             .split(',')
-            .map(|s| s.parse().unwrap())
-            .collect();
-        Self { reg_a, reg_b, reg_c, instructions, pointer: 0, result: Vec::new() }
+            .map(|s| Self::parse_instruction(line, s))
+            .collect::<Result<_, _>>()?;
+        if instructions.len() % 2 == 1 {
+            return Err(line.error_end(line.text, "`,` and an operand"));
+        }
+        let computer = Self { reg_a, reg_b, reg_c, instructions, pointer: 0, result: Vec::new() };
+        if valid(&computer) {
+            Ok(computer)
+        } else {
+            Err(line.error(program, "a loop that outputs once and shifts A by 3 bits per pass"))
+        }
+    }
+
+    /// Whether the program is a single loop ending in `jnz 0` whose body has no other jump,
+    /// outputs once, changes A only with one `adv 3`, and sets B and C before reading them.
+    /// Each pass then only depends on A, and each output on the bits of A that are left.
+    fn is_shift_loop(&self) -> bool {
+        let Some((body, [3, 0])) = self.instructions.split_last_chunk() else { return false };
+        // Each pass outputs one value and an A register of 63 bits runs out after 21 passes, so
+        // a longer program cannot output itself.
+        if body.len() > 19 {
+            return false;
+        }
+        let (mut b, mut c) = (false, false);
+        let (mut shifts, mut outputs) = (0, 0);
+        for pair in body.chunks_exact(2) {
+            let &[opcode, operand] = pair else { unreachable!() };
+            let combo = match operand {
+                5 => b,
+                6 => c,
+                7 => false,
+                _ => true,
+            };
+            let ready = match opcode {
+                0 | 2 | 5 | 6 | 7 => combo,
+                1 => b,
+                4 => b && c,
+                _ => false,
+            };
+            if !ready {
+                return false;
+            }
+            match (opcode, operand) {
+                (0, 3) => shifts += 1,
+                (0, _) => return false,
+                (5, _) => outputs += 1,
+                (7, _) => c = true,
+                _ => b = true,
+            }
+        }
+        shifts == 1 && outputs == 1
+    }
+
+    pub fn disassemble(&self) -> String { asm::disassemble(self) }
+
+    /// The output of the program when register A starts at `a`.
+    fn output(&self, a: i64) -> Result<Vec<u8>, Fault> {
+        let mut computer = Self { reg_a: a, pointer: 0, result: Vec::new(), ..self.clone() };
+        computer.run(true, usize::MAX)?;
+        Ok(computer.result)
+    }
+
+    fn parse_instruction(line: Line, s: &str) -> Result<u8, ParseError> {
+        line.parse(s, "a 3-bit number")
+            .and_then(|i| if i < 8 { Ok(i) } else { Err(line.error(s, "a 3-bit number")) })
     }
 }
 
-pub fn day17_a() {
-    let mut computer = Computer::parse(read_lines(17));
-    computer.run(true);
+pub fn day17_a(computer: &Computer) -> Result<Answer, SolveError> {
+    let mut computer = computer.clone();
+    if let Err(fault) = computer.run(true, usize::MAX) {
+        return Err(SolveError::new(17, format!("the program stops on a fault: {fault}")));
+    }
     let result = computer.get_str();
-    println!("{result}");
+    Ok(result.into())
 }
 
-const EXPECTED: [i64; 16] = [2, 4, 1, 1, 7, 5, 4, 6, 1, 4, 0, 3, 5, 5, 3, 0];
-
-fn find() -> i64 {
-    let mut potential: Vec<_> = (0..=7i64.pow(1)).filter(|&a| simulate(a).last() == Some(&0)).collect();
-    for count in 2..=EXPECTED.len() {
-        let mut next = Vec::new();
-        potential.into_iter().for_each(|a| check(a, count, &mut next));
-        potential = next;
+/// The smallest A for which a program accepted by [`Computer::parse_shift_loop`] outputs
+/// `expected`. The last output only depends on the top three bits of A, the one before on the
+/// top six and so on, so A is found three bits at a time from the most significant end.
+fn find(computer: &Computer, expected: &[u8]) -> Option<i64> {
+    let mut potential = vec![0];
+    for count in 1..=expected.len() {
+        let tail = &expected[expected.len() - count..];
+        potential = potential
+            .into_iter()
+            .flat_map(|previous: i64| (0..8).map(move |end| (previous << 3) | end))
+            .filter(|&a| computer.output(a).is_ok_and(|output| output == tail))
+            .collect();
     }
-    potential.into_iter().min().unwrap()
+    potential.into_iter().min()
 }
 
-fn check(previous: i64, count: usize, results: &mut Vec<i64>) {
-    for end in 0..=7 {
-        let a = (previous << 3) | end;
-        let result = simulate(a);
-        if result.len() >= count && result[result.len() - count..] == EXPECTED[16 - count..] {
-            results.push(a);
+pub fn day17_b(computer: &Computer) -> Result<Answer, SolveError> {
+    let result = find(computer, &computer.instructions)
+        .ok_or_else(|| SolveError::new(17, "no value of A makes the program output itself"))?;
+    Ok(result.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::Rng;
+
+    const EXAMPLE: &str = include_str!("examples/day17.txt");
+    const EXAMPLE_B: &str = include_str!("examples/day17b.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(
+            day17_a(&Computer::parse(EXAMPLE).unwrap()),
+            Ok(Answer::from("4,6,3,5,6,3,5,2,1,0"))
+        );
+        let input = "Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: 1,3,2,7";
+        assert_eq!(
+            day17_a(&Computer::parse(input).unwrap()).unwrap_err().to_string(),
+            "day 17 input: the program stops on a fault: reserved combo operand 7 at 2"
+        );
+    }
+
+    #[test]
+    fn rejects_negative_registers() {
+        let input = "Register A: 0\nRegister B: -1\nRegister C: 0\n\nProgram: 6,5";
+        assert_eq!(
+            Computer::parse(input).unwrap_err().to_string(),
+            "day 17 input, line 2, column 13: expected a number of at least 0"
+        );
+    }
+
+    #[test]
+    fn part_b() {
+        let computer = Computer::parse_shift_loop(EXAMPLE_B).unwrap();
+        assert_eq!(day17_b(&computer), Ok(Answer::from(117_440)));
+        // Outputs 1 on every pass.
+        let input = "Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: 0,3,2,1,5,5,3,0";
+        assert!(day17_b(&Computer::parse_shift_loop(input).unwrap()).is_err());
+    }
+
+    #[test]
+    fn rejects_other_programs() {
+        // The first example shifts by one bit per pass.
+        assert!(Computer::parse_shift_loop(EXAMPLE).is_err());
+        for program in ["0,3,5,4", "0,3,5,4,3,2,3,0", "0,3,1,1,5,5,3,0", "0,3,0,3,5,4,3,0"] {
+            let input =
+                format!("Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: {program}");
+            assert!(Computer::parse_shift_loop(&input).is_err(), "{program}");
+        }
+        // 21 passes output at most 10 instructions.
+        for (length, valid) in [(10, true), (11, false)] {
+            let program = format!("0,3,5,4,{}3,0", "2,1,".repeat(length - 3));
+            let input =
+                format!("Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: {program}");
+            assert_eq!(Computer::parse_shift_loop(&input).is_ok(), valid, "{program}");
         }
     }
-}
 
-fn simulate(start: i64) -> Vec<i64> {
-    let mut result = Vec::new();
-    let mut a = start;
-    while a != 0 {
-        let mut b = a & 0b111;
-        b ^= 0b1;
-        b ^= (a >> b) & 0b111;
-        b ^= 0b100;
-        a >>= 3;
-        result.push(b & 0b111);
-    }
-    result
-}
+    /// A program like the official inputs, with random constants.
+    fn program(rng: &mut Rng) -> Computer {
+        let [x, y] = [rng.below(8), rng.below(8)];
+        let input = format!(
+            "Register A: 0\nRegister B: 0\nRegister C: 0\n\n\
+             Program: 2,4,1,{x},7,5,4,6,1,{y},0,3,5,5,3,0"
+        );
+        Computer::parse_shift_loop(&input).unwrap()
+    }
 
-fn test() {
-    for test in 0o10..0o100 {
-        let base = simulate(test);
-        let moved = test << 3;
-        print!("{test:o}: {base:?} | ");
-        for x in 0..=7 {
-            let new = moved |x;
-            let result = simulate(new);
-            print!("{new:o}: {result:?} | ");
-            if base != result[result.len() - base.len()..] {
-                println!("{test:o}, {x}");
+    #[test]
+    fn octal_search_matches_scanning() {
+        let mut rng = Rng::new(17);
+        for digits in 1..=5 {
+            let limit = 8_i64.pow(digits);
+            for _ in 0..20 {
+                let computer = program(&mut rng);
+                let a = rng.range(1..=usize::try_from(limit - 1).unwrap());
+                let expected = computer.output(i64::try_from(a).unwrap()).unwrap();
+                let smallest = (0..limit).find(|&a| computer.output(a).unwrap() == expected);
+                assert_eq!(find(&computer, &expected), smallest, "output {expected:?}");
             }
         }
-        println!();
     }
 }
-
-pub fn day17_b() {
-    // test();
-    let result = find();
-    println!("{result}");
-}
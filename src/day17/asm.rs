@@ -0,0 +1,188 @@
+use std::fmt::Write as _;
+
+use super::{Computer, Line, Lines, ParseError};
+
+const MNEMONICS: [&str; 8] = ["adv", "bxl", "bst", "jnz", "bxc", "out", "bdv", "cdv"];
+
+/// Combo operands 4 to 6 read a register and 7 is reserved, which the assembler accepts as `7`.
+const COMBO: [&str; 8] = ["0", "1", "2", "3", "A", "B", "C", "7"];
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Operand {
+    Literal,
+    Combo,
+    /// `bxc` ignores its operand, but it is kept so that programs round-trip.
+    Ignored,
+}
+
+const fn operand(opcode: u8) -> Operand {
+    match opcode {
+        1 | 3 => Operand::Literal,
+        4 => Operand::Ignored,
+        _ => Operand::Combo,
+    }
+}
+
+/// The instruction as text, such as `cdv B`. A pair that is not an instruction, which a parsed
+/// program never contains, is shown as its two numbers.
+pub fn instruction(opcode: u8, value: u8) -> String {
+    let Some(mnemonic) = MNEMONICS.get(usize::from(opcode)) else {
+        return format!("{opcode},{value}");
+    };
+    match (operand(opcode), COMBO.get(usize::from(value))) {
+        (Operand::Combo, Some(combo)) => format!("{mnemonic} {combo}"),
+        (Operand::Literal | Operand::Ignored, _) if value < 8 => format!("{mnemonic} {value}"),
+        _ => format!("{opcode},{value}"),
+    }
+}
+
+/// One instruction per line, such as `4: cdv B`, with comments on jumps and their targets.
+/// Taking a parsed [`Computer`] guarantees whole instructions of 3-bit numbers.
+pub fn disassemble(computer: &Computer) -> String {
+    let instructions = &computer.instructions;
+    let width = instructions.len().saturating_sub(1).to_string().len();
+    let mut comments = vec![Vec::new(); instructions.len() / 2];
+    for (address, pair) in instructions.chunks_exact(2).enumerate().map(|(i, p)| (2 * i, p)) {
+        let &[3, target] = pair else { continue };
+        let target = usize::from(target);
+        let comment = if target >= instructions.len() {
+            format!("jump to {target}, which halts")
+        } else if target % 2 == 1 {
+            format!("jump to {target}, into an operand")
+        } else if target <= address {
+            format!("loop to {target}")
+        } else {
+            format!("jump to {target}")
+        };
+        comments[address / 2].push(comment);
+        if target < instructions.len() && target % 2 == 0 {
+            comments[target / 2].push(format!("<- jnz at {address}"));
+        }
+    }
+    let mut text = String::new();
+    for (i, (pair, comments)) in instructions.chunks_exact(2).zip(comments).enumerate() {
+        write!(text, "{:>width$}: {}", 2 * i, instruction(pair[0], pair[1])).unwrap();
+        if !comments.is_empty() {
+            write!(text, "  ; {}", comments.join(", ")).unwrap();
+        }
+        text.push('\n');
+    }
+    text
+}
+
+fn parse_operand(line: &Line, opcode: u8, s: &str) -> Result<u8, ParseError> {
+    match operand(opcode) {
+        Operand::Combo => COMBO
+            .iter()
+            .position(|&c| c == s)
+            .map(|i| u8::try_from(i).unwrap())
+            .ok_or_else(|| line.error(s, "a combo operand: 0-3, `A`, `B` or `C`")),
+        Operand::Literal | Operand::Ignored => match s.parse() {
+            Ok(value @ 0..=7) => Ok(value),
+            _ => Err(line.error(s, "a literal operand from 0 to 7")),
+        },
+    }
+}
+
+/// Parses the output of [`disassemble`] back into opcodes and operands. Addresses are
+/// optional but must match, and `;` starts a comment.
+pub fn assemble(text: &str) -> Result<Vec<u8>, ParseError> {
+    let mut instructions = Vec::new();
+    for line in Lines::new(17, text) {
+        let code = line.text.split_once(';').map_or(line.text, |(code, _)| code);
+        let mut words = code.split_whitespace().peekable();
+        if let Some(address) = words.next_if(|w| w.ends_with(':')) {
+            let expected = instructions.len();
+            if address[..address.len() - 1].parse() != Ok(expected) {
+                return Err(line.error(address, format!("address `{expected}:`")));
+            }
+        }
+        let Some(mnemonic) = words.next() else { continue };
+        let opcode = MNEMONICS
+            .iter()
+            .position(|&m| m == mnemonic)
+            .map(|i| u8::try_from(i).unwrap())
+            .ok_or_else(|| line.error(mnemonic, "a mnemonic such as `adv`"))?;
+        let value = line.take(&mut words, "an operand")?;
+        instructions.extend([opcode, parse_operand(&line, opcode, value)?]);
+        line.end(words)?;
+    }
+    Ok(instructions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::Rng;
+
+    fn program(instructions: &[u8]) -> Computer {
+        let program: Vec<_> = instructions.iter().map(u8::to_string).collect();
+        let input = format!(
+            "Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: {}",
+            program.join(",")
+        );
+        Computer::parse(&input).unwrap()
+    }
+
+    const PROGRAM: [u8; 16] = [2, 4, 1, 1, 7, 5, 4, 6, 1, 4, 0, 3, 5, 5, 3, 0];
+
+    #[test]
+    fn disassembles() {
+        assert_eq!(
+            disassemble(&program(&PROGRAM)),
+            " 0: bst A  ; <- jnz at 14
+ 2: bxl 1
+ 4: cdv B
+ 6: bxc 6
+ 8: bxl 4
+10: adv 3
+12: out B
+14: jnz 0  ; loop to 0
+"
+        );
+        assert_eq!(
+            disassemble(&program(&[3, 4, 3, 5, 3, 7])),
+            "0: jnz 4  ; jump to 4
+2: jnz 5  ; jump to 5, into an operand
+4: jnz 7  ; <- jnz at 0, jump to 7, which halts
+"
+        );
+        assert_eq!(instruction(5, 7), "out 7");
+        assert_eq!(instruction(9, 1), "9,1");
+        assert_eq!(instruction(5, 8), "5,8");
+        assert_eq!(instruction(1, 8), "1,8");
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = Rng::new(17);
+        for _ in 0..200 {
+            let length = 2 * rng.range(1..=10);
+            let instructions: Vec<_> =
+                (0..length).map(|_| *rng.pick(&[0, 1, 2, 3, 4, 5, 6, 7])).collect();
+            assert_eq!(assemble(&disassemble(&program(&instructions))).unwrap(), instructions);
+        }
+        let text = "bst A\n\n; comment\n  2: bxl 1 ; note\nout 3\n";
+        assert_eq!(assemble(text).unwrap(), [2, 4, 1, 1, 5, 3]);
+    }
+
+    #[test]
+    fn errors() {
+        let error = |text| assemble(text).unwrap_err().to_string();
+        assert_eq!(error("bst"), "day 17 input, line 1, column 4: expected an operand");
+        assert_eq!(
+            error("bst A\nfoo 1"),
+            "day 17 input, line 2, column 1: expected a mnemonic such as `adv`"
+        );
+        assert_eq!(
+            error("bxl B"),
+            "day 17 input, line 1, column 5: expected a literal operand from 0 to 7"
+        );
+        assert_eq!(
+            error("out D"),
+            "day 17 input, line 1, column 5: expected a combo operand: 0-3, `A`, `B` or `C`"
+        );
+        assert_eq!(error("4: out A"), "day 17 input, line 1, column 1: expected address `0:`");
+        assert_eq!(error("out A A"), "day 17 input, line 1, column 7: expected end of line");
+    }
+}
@@ -0,0 +1,295 @@
+use std::ops::Range;
+use std::time::Duration;
+
+use super::debug::Register;
+use super::{Computer, Fault};
+use crate::output::format_duration;
+use crate::runner::time;
+use crate::table::Table;
+use crate::utils::parallel_map;
+
+/// How many instructions the searches and [`Compiled::output`] execute for one value of A before
+/// giving up, so that programs which never halt cannot stall them.
+const STEP_LIMIT: usize = 1 << 20;
+
+/// How many values of A one thread checks before taking the next range.
+const CHUNK: i64 = 1 << 14;
+
+/// A combo operand, decoded once.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Value {
+    Literal(i64),
+    Register(Register),
+}
+
+impl Value {
+    fn decode(operand: u8) -> Option<Self> {
+        match operand {
+            0..=3 => Some(Self::Literal(operand.into())),
+            4 => Some(Self::Register(Register::A)),
+            5 => Some(Self::Register(Register::B)),
+            6 => Some(Self::Register(Register::C)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Op {
+    /// `adv`, `bdv` or `cdv`.
+    Divide(Register, Value),
+    /// `bxl`.
+    XorB(i64),
+    /// `bst`, followed by `bxl` with the given literal, or 0 on its own.
+    Low(Value, i64),
+    /// `bxc`, followed by `bxl` with the given literal, or 0 on its own.
+    XorBC(i64),
+    /// `out`.
+    Out(Value),
+    /// `out` followed by `jnz`.
+    OutJump(Value, usize),
+    /// `jnz`.
+    Jump(usize),
+    /// An instruction with combo operand 7, which faults when reached.
+    Reserved,
+}
+
+impl Op {
+    /// How many program values the operation covers.
+    const fn width(self) -> usize {
+        match self {
+            Self::Low(_, 1..) | Self::XorBC(1..) | Self::OutJump(..) => 4,
+            _ => 2,
+        }
+    }
+}
+
+/// A day 17 program decoded ahead of time, so that running it for many values of A skips
+/// the operand decoding and the output vector of [`Computer`].
+///
+/// There is an operation for every address, not just the even ones, so jumps into an operand
+/// behave as in the interpreter. Common pairs such as `bst` then `bxl` are fused into one
+/// operation; a jump to the second instruction of a pair still finds its own operation.
+#[derive(Clone, Debug)]
+pub struct Compiled {
+    ops: Vec<Op>,
+    b: i64,
+    c: i64,
+}
+
+fn decode(instructions: &[u8], address: usize) -> Op {
+    let [opcode, operand] = [instructions[address], instructions[address + 1]];
+    let next = instructions.get(address + 2..address + 4);
+    match (opcode, Value::decode(operand), next) {
+        (1, _, _) => Op::XorB(operand.into()),
+        (3, _, _) => Op::Jump(operand.into()),
+        (4, _, Some(&[1, k])) => Op::XorBC(k.into()),
+        (4, _, _) => Op::XorBC(0),
+        (_, None, _) => Op::Reserved,
+        (0, Some(value), _) => Op::Divide(Register::A, value),
+        (2, Some(value), Some(&[1, k])) => Op::Low(value, k.into()),
+        (2, Some(value), _) => Op::Low(value, 0),
+        (5, Some(value), Some(&[3, target])) => Op::OutJump(value, target.into()),
+        (5, Some(value), _) => Op::Out(value),
+        (6, Some(value), _) => Op::Divide(Register::B, value),
+        (7, Some(value), _) => Op::Divide(Register::C, value),
+        _ => unreachable!(),
+    }
+}
+
+/// `numerator / 2^power`, a shift as in the interpreter, since registers are never negative.
+fn divide(numerator: i64, power: i64) -> i64 {
+    let power = u32::try_from(power).unwrap_or(u32::MAX);
+    numerator.checked_shr(power).unwrap_or(0)
+}
+
+impl Compiled {
+    pub fn new(computer: &Computer) -> Self {
+        let instructions = &computer.instructions;
+        let ops = (0..instructions.len().saturating_sub(1))
+            .map(|address| decode(instructions, address))
+            .collect();
+        Self { ops, b: computer.reg_b, c: computer.reg_c }
+    }
+
+    /// Runs the program with A set to `a`, passing each output to `out` until it returns
+    /// `false`. Returns whether the program halted within `limit` operations.
+    fn execute(
+        &self, a: i64, limit: usize, mut out: impl FnMut(u8) -> bool,
+    ) -> Result<bool, Fault> {
+        let (mut a, mut b, mut c) = (a, self.b, self.c);
+        let mut pointer = 0;
+        for _ in 0..limit {
+            let Some(&op) = self.ops.get(pointer) else { return Ok(true) };
+            let get = |value| match value {
+                Value::Literal(n) => n,
+                Value::Register(Register::A) => a,
+                Value::Register(Register::B) => b,
+                Value::Register(Register::C) => c,
+            };
+            match op {
+                Op::Divide(register, value) => {
+                    let result = divide(a, get(value));
+                    match register {
+                        Register::A => a = result,
+                        Register::B => b = result,
+                        Register::C => c = result,
+                    }
+                },
+                Op::XorB(k) => b ^= k,
+                Op::Low(value, k) => b = (get(value) & 0b111) ^ k,
+                Op::XorBC(k) => b ^= c ^ k,
+                Op::Out(value) | Op::OutJump(value, _) => {
+                    #[expect(clippy::cast_sign_loss)]
+                    let value = (get(value) & 0b111) as u8;
+                    if !out(value) {
+                        return Ok(false);
+                    }
+                },
+                Op::Jump(_) => {},
+                Op::Reserved => return Err(Fault::ReservedOperand(pointer)),
+            }
+            pointer = match op {
+                Op::Jump(target) | Op::OutJump(_, target) if a != 0 => target,
+                _ => pointer + op.width(),
+            };
+        }
+        Ok(false)
+    }
+
+    /// The output of the program when register A starts at `a`, like `Computer::output`, or
+    /// [`Fault::StepLimit`] if it does not halt within [`STEP_LIMIT`] operations.
+    pub fn output(&self, a: i64) -> Result<Vec<u8>, Fault> {
+        let mut output = Vec::new();
+        let halted = self.execute(a, STEP_LIMIT, |value| {
+            output.push(value);
+            true
+        })?;
+        if halted { Ok(output) } else { Err(Fault::StepLimit(STEP_LIMIT)) }
+    }
+
+    /// Whether the program outputs exactly `expected` when register A starts at `a`, giving up
+    /// as soon as the output differs.
+    pub fn matches(&self, a: i64, expected: &[u8]) -> bool {
+        let mut count = 0;
+        let halted = self.execute(a, STEP_LIMIT, |value| {
+            count += 1;
+            expected.get(count - 1) == Some(&value)
+        });
+        halted == Ok(true) && count == expected.len()
+    }
+
+    /// How many values of A in `candidates` make the program output `expected`, counted on
+    /// `jobs` threads.
+    fn count(&self, expected: &[u8], candidates: Range<i64>, jobs: usize) -> usize {
+        let counts = parallel_map(&chunks(candidates), jobs, |range| {
+            range.clone().filter(|&a| self.matches(a, expected)).count()
+        });
+        counts.into_iter().sum()
+    }
+}
+
+fn chunks(candidates: Range<i64>) -> Vec<Range<i64>> {
+    let starts = candidates.clone().step_by(usize::try_from(CHUNK).unwrap());
+    starts.map(|start| start..(start + CHUNK).min(candidates.end)).collect()
+}
+
+/// Whether `machine`, reset to `start` with A set to `a`, outputs its own program within
+/// [`STEP_LIMIT`] instructions, run by [`Computer::run`] as a brute-force part b would.
+fn interpret(machine: &mut Computer, start: &Computer, a: i64) -> bool {
+    (machine.reg_a, machine.reg_b, machine.reg_c) = (a, start.reg_b, start.reg_c);
+    machine.pointer = 0;
+    machine.result.clear();
+    machine.run(false, STEP_LIMIT) == Ok(true)
+}
+
+/// Checks A from 0 to `count` for whether the program outputs itself, with the interpreter,
+/// the compiled program and the compiled program on `jobs` threads, and tabulates the times.
+#[expect(clippy::cast_precision_loss)]
+pub fn benchmark(computer: &Computer, count: i64, jobs: usize) -> String {
+    let compiled = Compiled::new(computer);
+    let expected = &computer.instructions;
+    let mut machine = computer.clone();
+    let mut table = Table::new(&["Method", "Values of A", "Matches", "Time", "Per second"]);
+    let mut add = |name: String, (matches, elapsed): (usize, Duration)| {
+        let rate = count as f64 / elapsed.as_secs_f64();
+        table.push(vec![
+            name,
+            count.to_string(),
+            matches.to_string(),
+            format_duration(elapsed),
+            format!("{:.1}M", rate / 1e6),
+        ]);
+    };
+    let interpreted = time(|| (0..count).filter(|&a| interpret(&mut machine, computer, a)).count());
+    add("interpreter".into(), interpreted);
+    add("compiled".into(), time(|| compiled.count(expected, 0..count, 1)));
+    let threads = if jobs == 1 { "thread" } else { "threads" };
+    add(format!("compiled, {jobs} {threads}"), time(|| compiled.count(expected, 0..count, jobs)));
+    table.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::Rng;
+
+    const EXAMPLE: &str = include_str!("../examples/day17.txt");
+    const EXAMPLE_B: &str = include_str!("../examples/day17b.txt");
+
+    fn program(instructions: &[u8], b: i64, c: i64) -> Computer {
+        Computer {
+            reg_a: 0,
+            reg_b: b,
+            reg_c: c,
+            instructions: instructions.to_vec(),
+            pointer: 0,
+            result: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn matches_interpreter() {
+        let computer = Computer::parse(EXAMPLE).unwrap();
+        assert_eq!(Compiled::new(&computer).output(729), computer.output(729));
+        // Random programs jump into operands, fault on combo operand 7 and loop forever, so
+        // compare the outputs of the runs that halt quickly.
+        let mut rng = Rng::new(17);
+        for _ in 0..2000 {
+            let length = 2 * rng.range(1..=8);
+            let instructions: Vec<_> =
+                (0..length).map(|_| *rng.pick(&[0, 1, 2, 3, 4, 5, 6, 7])).collect();
+            let computer = program(&instructions, rng.range(0..=100).try_into().unwrap(), 5);
+            let compiled = Compiled::new(&computer);
+            for a in [0, 1, 7, 8, 12_345] {
+                let mut interpreter = Computer { reg_a: a, ..computer.clone() };
+                let expected = (0..1000).find_map(|_| match interpreter.step() {
+                    Ok(true) => None,
+                    Ok(false) => Some(Ok(interpreter.result.clone())),
+                    Err(fault) => Some(Err(fault)),
+                });
+                if let Some(expected) = expected {
+                    assert_eq!(compiled.output(a), expected, "{instructions:?} with A={a}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn counts_matches() {
+        let computer = Computer::parse(EXAMPLE_B).unwrap();
+        let compiled = Compiled::new(&computer);
+        for jobs in [1, 3] {
+            assert_eq!(compiled.count(&computer.instructions, 0..117_440, jobs), 0);
+            assert_eq!(compiled.count(&computer.instructions, 0..117_441, jobs), 1);
+        }
+        assert_eq!(compiled.count(&computer.instructions, 100_000..300_000, 2), 8);
+        let mut machine = computer.clone();
+        let interpreted = (100_000..300_000).filter(|&a| interpret(&mut machine, &computer, a));
+        assert_eq!(interpreted.count(), 8);
+        assert!(!Compiled::new(&program(&[3, 0], 0, 0)).matches(1, &[]));
+        let error = Compiled::new(&program(&[3, 0], 0, 0)).output(1);
+        assert_eq!(error, Err(Fault::StepLimit(STEP_LIMIT)));
+        assert!(!interpret(&mut program(&[3, 0], 0, 0), &program(&[3, 0], 0, 0), 1));
+    }
+}
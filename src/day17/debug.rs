@@ -0,0 +1,419 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter, Result as FmtResult, Write as _};
+use std::io::{self, BufRead, Write};
+
+use super::asm::{disassemble, instruction};
+use super::{Computer, Fault};
+
+/// How many instructions `continue` executes before giving control back, in case the program
+/// never halts.
+const CONTINUE_LIMIT: usize = 10_000_000;
+
+const PROMPT: &str = "(day17) ";
+
+const HELP: &str = "\
+s, step [<n>]         execute <n> instructions, defaults to 1, and print each
+c, continue           execute until a breakpoint, a watched register changes or the program ends
+b, break <pointer>    stop before the instruction at <pointer>
+b, break out <n>      stop once the output has <n> values
+w, watch <register>   stop when register A, B or C changes
+d, delete             remove all breakpoints and watches
+set <register> <n>    set register A, B or C
+r, regs               show the pointer, registers and output
+l, list               show the program, marking the pointer with `>` and breakpoints with `*`
+reset                 start over with the initial registers
+q, quit               leave the debugger
+";
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Register {
+    A,
+    B,
+    C,
+}
+
+impl Register {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "a" | "A" => Some(Self::A),
+            "b" | "B" => Some(Self::B),
+            "c" | "C" => Some(Self::C),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Register {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::A => write!(f, "A"),
+            Self::B => write!(f, "B"),
+            Self::C => write!(f, "C"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Registers {
+    pub a: i64,
+    pub b: i64,
+    pub c: i64,
+}
+
+impl Registers {
+    pub const fn get(&self, register: Register) -> i64 {
+        match register {
+            Register::A => self.a,
+            Register::B => self.b,
+            Register::C => self.c,
+        }
+    }
+}
+
+impl Display for Registers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "A={} B={} C={}", self.a, self.b, self.c)
+    }
+}
+
+/// An executed instruction with the registers before and after it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Step {
+    pub pointer: usize,
+    pub opcode: u8,
+    pub operand: u8,
+    pub before: Registers,
+    pub after: Registers,
+    pub output: Option<u8>,
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let instruction = instruction(self.opcode, self.operand);
+        write!(f, "{:>2}: {instruction:<6} {}", self.pointer, self.before)?;
+        let changed: Vec<_> = [Register::A, Register::B, Register::C]
+            .into_iter()
+            .filter(|&r| self.before.get(r) != self.after.get(r))
+            .map(|r| format!("{r}={}", self.after.get(r)))
+            .collect();
+        if !changed.is_empty() {
+            write!(f, " -> {}", changed.join(" "))?;
+        }
+        if let Some(output) = self.output {
+            write!(f, " -> output {output}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Why execution stopped.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Stop {
+    Halted,
+    Fault(Fault),
+    /// The given number of instructions ran without any other reason to stop.
+    Limit(usize),
+    Breakpoint(usize),
+    Output(usize),
+    Watch(Register, i64, i64),
+}
+
+impl Display for Stop {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Halted => write!(f, "halted"),
+            Self::Fault(fault) => write!(f, "fault: {fault}"),
+            Self::Limit(n) => write!(f, "paused after {n} instructions"),
+            Self::Breakpoint(p) => write!(f, "breakpoint at {p}"),
+            Self::Output(1) => write!(f, "output has 1 value"),
+            Self::Output(n) => write!(f, "output has {n} values"),
+            Self::Watch(r, old, new) => write!(f, "{r} changed from {old} to {new}"),
+        }
+    }
+}
+
+impl Computer {
+    pub const fn registers(&self) -> Registers {
+        Registers { a: self.reg_a, b: self.reg_b, c: self.reg_c }
+    }
+
+    /// Executes one instruction like [`Computer::step`] and returns it, or `None` if the
+    /// program has halted.
+    pub fn step_traced(&mut self) -> Result<Option<Step>, Fault> {
+        let (pointer, before, outputs) = (self.pointer, self.registers(), self.result.len());
+        if !self.step()? {
+            return Ok(None);
+        }
+        Ok(Some(Step {
+            pointer,
+            opcode: self.instructions[pointer],
+            operand: self.instructions[pointer + 1],
+            before,
+            after: self.registers(),
+            output: self.result.get(outputs).copied(),
+        }))
+    }
+
+    /// Runs until the program halts, faults or has executed `limit` instructions, recording
+    /// every instruction.
+    pub fn trace(&mut self, limit: usize) -> (Vec<Step>, Stop) {
+        let mut steps = Vec::new();
+        while steps.len() < limit {
+            match self.step_traced() {
+                Ok(Some(step)) => steps.push(step),
+                Ok(None) => return (steps, Stop::Halted),
+                Err(fault) => return (steps, Stop::Fault(fault)),
+            }
+        }
+        (steps, Stop::Limit(limit))
+    }
+}
+
+/// A stepper over a [`Computer`] driven by text commands, see [`HELP`].
+pub struct Debugger {
+    start: Computer,
+    computer: Computer,
+    breakpoints: BTreeSet<usize>,
+    output_lengths: BTreeSet<usize>,
+    watches: BTreeSet<Register>,
+}
+
+impl Debugger {
+    pub fn new(computer: Computer) -> Self {
+        Self {
+            start: computer.clone(),
+            computer,
+            breakpoints: BTreeSet::new(),
+            output_lengths: BTreeSet::new(),
+            watches: BTreeSet::new(),
+        }
+    }
+
+    /// Runs one command and returns what to print, or `None` if the command was `quit`.
+    pub fn command(&mut self, line: &str) -> Option<String> {
+        let words: Vec<_> = line.split_whitespace().collect();
+        let reply = match words[..] {
+            [] => Ok(String::new()),
+            ["q" | "quit"] => return None,
+            ["s" | "step"] => Ok(self.execute(1, true)),
+            ["s" | "step", n] =>
+                parse(n, "a number of instructions").map(|n| self.execute(n, true)),
+            ["c" | "continue"] => Ok(self.execute(CONTINUE_LIMIT, false)),
+            ["b" | "break", "out", n] => parse(n, "an output length").map(|n| {
+                self.output_lengths.insert(n);
+                format!("stopping once the output has {n} values\n")
+            }),
+            ["b" | "break", p] => parse(p, "a pointer").map(|p| {
+                self.breakpoints.insert(p);
+                format!("breakpoint at {p}\n")
+            }),
+            ["w" | "watch", r] => register(r).map(|r| {
+                self.watches.insert(r);
+                format!("watching {r}\n")
+            }),
+            ["d" | "delete"] => {
+                self.breakpoints.clear();
+                self.output_lengths.clear();
+                self.watches.clear();
+                Ok("removed all breakpoints and watches\n".into())
+            },
+            ["set", r, text] => register(r).and_then(|r| {
+                let expected = "a register value of at least 0";
+                let value = parse(text, expected)?;
+                if value < 0 {
+                    return Err(format!("expected {expected}, found `{text}`"));
+                }
+                let slot = match r {
+                    Register::A => &mut self.computer.reg_a,
+                    Register::B => &mut self.computer.reg_b,
+                    Register::C => &mut self.computer.reg_c,
+                };
+                *slot = value;
+                Ok(self.state())
+            }),
+            ["r" | "regs"] => Ok(self.state()),
+            ["l" | "list"] => Ok(self.list()),
+            ["reset"] => {
+                self.computer = self.start.clone();
+                Ok(self.state())
+            },
+            ["h" | "help"] => Ok(HELP.into()),
+            _ => Err(format!("unknown command `{}`, try `help`", line.trim())),
+        };
+        Some(reply.unwrap_or_else(|e| format!("error: {e}\n")))
+    }
+
+    fn state(&self) -> String {
+        let output: Vec<_> = self.computer.result.iter().map(u8::to_string).collect();
+        let c = &self.computer;
+        format!("pointer {}, {}, output [{}]\n", c.pointer, c.registers(), output.join(","))
+    }
+
+    fn list(&self) -> String {
+        let mut text = String::new();
+        for (i, line) in disassemble(&self.computer).lines().enumerate() {
+            let pointer = if self.computer.pointer == 2 * i { '>' } else { ' ' };
+            let breakpoint = if self.breakpoints.contains(&(2 * i)) { '*' } else { ' ' };
+            writeln!(text, "{pointer}{breakpoint} {line}").unwrap();
+        }
+        text
+    }
+
+    /// Executes up to `limit` instructions, printing each if `echo` is set, until something
+    /// the user asked to stop for happens.
+    fn execute(&mut self, limit: usize, echo: bool) -> String {
+        let mut text = String::new();
+        let mut end = Stop::Limit(limit);
+        for _ in 0..limit {
+            let step = match self.computer.step_traced() {
+                Ok(Some(step)) => step,
+                Ok(None) => {
+                    end = Stop::Halted;
+                    break;
+                },
+                Err(fault) => {
+                    end = Stop::Fault(fault);
+                    break;
+                },
+            };
+            if echo {
+                writeln!(text, "{step}").unwrap();
+            }
+            if let Some(reason) = self.reason(&step) {
+                end = reason;
+                break;
+            }
+        }
+        // A finished `step <n>` needs no explanation.
+        if !(echo && end == Stop::Limit(limit)) {
+            writeln!(text, "{end}").unwrap();
+        }
+        text + &self.state()
+    }
+
+    fn reason(&self, step: &Step) -> Option<Stop> {
+        let watch = self.watches.iter().find(|&&r| step.before.get(r) != step.after.get(r));
+        let outputs = self.computer.result.len();
+        if let Some(&r) = watch {
+            Some(Stop::Watch(r, step.before.get(r), step.after.get(r)))
+        } else if step.output.is_some() && self.output_lengths.contains(&outputs) {
+            Some(Stop::Output(outputs))
+        } else if self.breakpoints.contains(&self.computer.pointer) {
+            Some(Stop::Breakpoint(self.computer.pointer))
+        } else {
+            None
+        }
+    }
+}
+
+fn parse<T: std::str::FromStr>(s: &str, expected: &str) -> Result<T, String> {
+    s.parse().map_err(|_| format!("expected {expected}, found `{s}`"))
+}
+
+fn register(s: &str) -> Result<Register, String> {
+    Register::parse(s).ok_or_else(|| format!("expected register A, B or C, found `{s}`"))
+}
+
+/// Reads debugger commands from `input` until `quit` or the end of input, answering on
+/// `output`.
+pub fn repl(computer: Computer, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let mut debugger = Debugger::new(computer);
+    write!(output, "{}{PROMPT}", debugger.state())?;
+    output.flush()?;
+    for line in input.lines() {
+        let Some(reply) = debugger.command(&line?) else { break };
+        write!(output, "{reply}{PROMPT}")?;
+        output.flush()?;
+    }
+    writeln!(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../examples/day17.txt");
+
+    fn computer(program: &str, a: i64) -> Computer {
+        let input = format!("Register A: {a}\nRegister B: 0\nRegister C: 0\n\nProgram: {program}");
+        Computer::parse(&input).unwrap()
+    }
+
+    #[test]
+    fn traces() {
+        let (steps, stop) = computer("0,1,5,4,3,0", 4).trace(100);
+        assert_eq!(stop, Stop::Halted);
+        let lines: Vec<_> = steps.iter().map(ToString::to_string).collect();
+        assert_eq!(lines, [
+            " 0: adv 1  A=4 B=0 C=0 -> A=2",
+            " 2: out A  A=2 B=0 C=0 -> output 2",
+            " 4: jnz 0  A=2 B=0 C=0",
+            " 0: adv 1  A=2 B=0 C=0 -> A=1",
+            " 2: out A  A=1 B=0 C=0 -> output 1",
+            " 4: jnz 0  A=1 B=0 C=0",
+            " 0: adv 1  A=1 B=0 C=0 -> A=0",
+            " 2: out A  A=0 B=0 C=0 -> output 0",
+            " 4: jnz 0  A=0 B=0 C=0",
+        ]);
+        let (steps, stop) = computer("0,0,3,0", 1).trace(10);
+        assert_eq!((steps.len(), stop), (10, Stop::Limit(10)));
+    }
+
+    #[test]
+    fn faults() {
+        let (steps, stop) = computer("1,3,2,7", 0).trace(10);
+        assert_eq!(steps.len(), 1);
+        assert_eq!(stop, Stop::Fault(Fault::ReservedOperand(2)));
+        assert_eq!(stop.to_string(), "fault: reserved combo operand 7 at 2");
+        let mut debugger = Debugger::new(computer("1,3,2,7", 0));
+        assert_eq!(
+            debugger.command("continue").unwrap(),
+            "fault: reserved combo operand 7 at 2\npointer 2, A=0 B=3 C=0, output []\n"
+        );
+    }
+
+    #[test]
+    fn stepper() {
+        let mut debugger = Debugger::new(Computer::parse(EXAMPLE).unwrap());
+        let mut run = |command| debugger.command(command).unwrap();
+        assert_eq!(
+            run("step 2"),
+            " 0: adv 1  A=729 B=0 C=0 -> A=364\n 2: out A  A=364 B=0 C=0 -> output 4\n\
+             pointer 4, A=364 B=0 C=0, output [4]\n"
+        );
+        assert_eq!(run("break 2"), "breakpoint at 2\n");
+        assert_eq!(run("c"), "breakpoint at 2\npointer 2, A=182 B=0 C=0, output [4]\n");
+        assert_eq!(
+            run("list"),
+            "   0: adv 1  ; <- jnz at 4\n>* 2: out A\n   4: jnz 0  ; loop to 0\n"
+        );
+        assert_eq!(run("delete"), "removed all breakpoints and watches\n");
+        assert_eq!(run("break out 4"), "stopping once the output has 4 values\n");
+        assert_eq!(run("c"), "output has 4 values\npointer 4, A=45 B=0 C=0, output [4,6,3,5]\n");
+        assert_eq!(run("watch a"), "watching A\n");
+        assert_eq!(
+            run("c"),
+            "A changed from 45 to 22\npointer 2, A=22 B=0 C=0, output [4,6,3,5]\n"
+        );
+        assert_eq!(run("set A 0"), "pointer 2, A=0 B=0 C=0, output [4,6,3,5]\n");
+        assert_eq!(run("d"), "removed all breakpoints and watches\n");
+        assert_eq!(run("c"), "halted\npointer 6, A=0 B=0 C=0, output [4,6,3,5,0]\n");
+        assert_eq!(run("step"), "halted\npointer 6, A=0 B=0 C=0, output [4,6,3,5,0]\n");
+        assert_eq!(run("reset"), "pointer 0, A=729 B=0 C=0, output []\n");
+        assert_eq!(run("break x"), "error: expected a pointer, found `x`\n");
+        assert_eq!(run("set B -1"), "error: expected a register value of at least 0, found `-1`\n");
+        assert_eq!(run("jump"), "error: unknown command `jump`, try `help`\n");
+        assert_eq!(debugger.command("quit"), None);
+    }
+
+    #[test]
+    fn session() {
+        let mut output = Vec::new();
+        repl(Computer::parse(EXAMPLE).unwrap(), &b"s\nq\ns\n"[..], &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "pointer 0, A=729 B=0 C=0, output []\n(day17)  0: adv 1  A=729 B=0 C=0 -> A=364\n\
+             pointer 2, A=364 B=0 C=0, output []\n(day17) \n"
+        );
+    }
+}
@@ -1,69 +1,115 @@
-use std::collections::HashSet;
-
-use super::{SplitOnceArr as _, read_lines};
+use super::{Answer, Direction, Lines, ParseError, SolveError, Vec2, astar};
 
 const SIZE: usize = 71;
+const BYTES: usize = 1024;
 
-fn display(grid: &[[bool; SIZE]; SIZE]) {
+fn display<const SIZE: usize>(grid: &[[bool; SIZE]; SIZE]) {
     let chars = grid.each_ref().map(|r| r.map(|b| if b { b'#' } else { b'.' }));
     let rows = chars.each_ref().map(|r| std::str::from_utf8(r).unwrap());
     let display = rows.join("\n");
     println!("{display}");
 }
 
-fn get_data() -> ([[bool; SIZE]; SIZE], impl Iterator<Item = [usize; 2]>) {
-    let mut grid = [[false; SIZE]; SIZE];
-    let mut coords = read_lines(18)
-        .map(|l| l.split_once_arr(',').unwrap().map(|n| n.parse::<usize>().unwrap()));
-    coords.by_ref().take(1024) .for_each(|[x, y]| grid[y][x] = true);
-    (grid, coords)
+/// The falling bytes, however many there are.
+fn get_coords(input: &str) -> Result<Vec<[usize; 2]>, ParseError> {
+    Lines::new(18, input)
+        .map(|l| {
+            let coordinate = |s| {
+                l.parse(s, "a coordinate")
+                    .and_then(|c| if c < SIZE { Ok(c) } else { Err(l.error(s, "a coordinate")) })
+            };
+            let [x, y] = l.split_once(l.text, ',', "`,`")?;
+            Ok([coordinate(x)?, coordinate(y)?])
+        })
+        .collect()
 }
 
-fn check_grid(grid: &[[bool; SIZE]; SIZE]) -> Option<u16> {
-    let mut scores = [[u16::MAX; SIZE]; SIZE];
-    scores[0][0] = 0;
-    let mut to_check = HashSet::from([(0, 0)]);
-    while !to_check.is_empty() {
-        let mut next = HashSet::new();
-        for (x, y) in to_check {
-            let s = scores[y][x];
-            if x > 0 && !grid[y][x - 1] && scores[y][x - 1] > s + 1 {
-                scores[y][x - 1] = s + 1;
-                next.insert((x - 1, y));
-            }
-            if y > 0 && !grid[y - 1][x] && scores[y - 1][x] > s + 1 {
-                scores[y - 1][x] = s + 1;
-                next.insert((x, y - 1));
-            }
-            if x < SIZE - 1 && !grid[y][x + 1] && scores[y][x + 1] > s + 1 {
-                scores[y][x + 1] = s + 1;
-                next.insert((x + 1, y));
-            }
-            if y < SIZE - 1 && !grid[y + 1][x] && scores[y + 1][x] > s + 1 {
-                scores[y + 1][x] = s + 1;
-                next.insert((x, y + 1));
-            }
-        }
-        to_check = next;
+pub fn get_data(input: &str) -> Result<Vec<[usize; 2]>, ParseError> {
+    let coords = get_coords(input)?;
+    if coords.len() < BYTES {
+        return Err(ParseError::new(18, coords.len() + 1, 1, format!("at least {BYTES} bytes")));
     }
-    let result = scores[SIZE - 1][SIZE - 1];
-    (result != u16::MAX).then_some(result)
+    Ok(coords)
 }
 
-pub fn day18_a() { 
-    let (grid, _) = get_data();
-    let result = check_grid(&grid).unwrap(); 
-    println!("{result}");
+fn get_grid<const SIZE: usize>(coords: &[[usize; 2]]) -> [[bool; SIZE]; SIZE] {
+    let mut grid = [[false; SIZE]; SIZE];
+    for &[x, y] in coords {
+        grid[y][x] = true;
+    }
+    grid
 }
 
-pub fn day18_b() {
-    let (mut grid, mut rest) = get_data();
-    loop {
-        let [x, y] = rest.next().unwrap();
+fn check_grid<const SIZE: usize>(grid: &[[bool; SIZE]; SIZE]) -> Option<u16> {
+    let size = Vec2::new(SIZE, SIZE);
+    let end = Vec2::new(SIZE - 1, SIZE - 1);
+    let successors = |p: Vec2<usize>| {
+        Direction::CARDINAL
+            .into_iter()
+            .filter_map(move |d| p.step_within(d, size))
+            .filter(|n| !grid[n.y][n.x])
+    };
+    let heuristic = |p: Vec2<usize>| end.x - p.x + end.y - p.y;
+    let (result, _) = astar(Vec2::new(0, 0), successors, |_, _| 1, heuristic, |p| p == end)?;
+    Some(result.try_into().unwrap())
+}
+
+/// The length of the shortest path once the first `bytes` bytes have fallen, or `None` if they
+/// block every path.
+pub fn shortest_path<const SIZE: usize>(coords: &[[usize; 2]], bytes: usize) -> Option<u16> {
+    let grid = get_grid::<SIZE>(&coords[..bytes]);
+    check_grid(&grid)
+}
+
+/// The first byte after the first `bytes` that blocks every path, or `None` if none does.
+pub fn first_blocking<const SIZE: usize>(
+    coords: &[[usize; 2]], bytes: usize,
+) -> Option<[usize; 2]> {
+    let mut grid = get_grid::<SIZE>(&coords[..bytes]);
+    coords[bytes..].iter().copied().find(|&[x, y]| {
         grid[y][x] = true;
-        if check_grid(&grid).is_none() {
-            println!("{x},{y}");
-            return;
-        }
+        check_grid(&grid).is_none()
+    })
+}
+
+pub fn day18_a(coords: &[[usize; 2]]) -> Result<Answer, SolveError> {
+    let result = shortest_path::<SIZE>(coords, BYTES)
+        .ok_or_else(|| SolveError::new(18, format!("the first {BYTES} bytes block every path")))?;
+    Ok(result.into())
+}
+
+pub fn day18_b(coords: &[[usize; 2]]) -> Result<Answer, SolveError> {
+    let [x, y] = first_blocking::<SIZE>(coords, BYTES)
+        .ok_or_else(|| SolveError::new(18, "no byte blocks every path"))?;
+    Ok(format!("{x},{y}").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("examples/day18.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(shortest_path::<7>(&get_coords(EXAMPLE).unwrap(), 12), Some(22));
+        assert_eq!(shortest_path::<7>(&get_coords(EXAMPLE).unwrap(), 21), None);
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(first_blocking::<7>(&get_coords(EXAMPLE).unwrap(), 12), Some([6, 1]));
+        assert_eq!(first_blocking::<7>(&get_coords(EXAMPLE).unwrap()[..20], 12), None);
+    }
+
+    #[test]
+    fn needs_enough_bytes() {
+        assert_eq!(
+            get_data("1,2\n3,4").unwrap_err().to_string(),
+            "day 18 input, line 3, column 1: expected at least 1024 bytes"
+        );
+        let input = "0,1\n1,0\n".repeat(BYTES / 2);
+        assert!(day18_a(&get_data(&input).unwrap()).is_err());
+        assert!(day18_b(&get_data(&input).unwrap()).is_err());
     }
 }
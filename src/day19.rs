@@ -1,25 +1,24 @@
 use std::collections::{HashMap, HashSet};
 
-use super::read_lines;
+use super::{Answer, Lines, ParseError};
 
 type Known = HashMap<Box<[u8]>, bool>;
 type Counts = HashMap<Box<[u8]>, usize>;
 type Towels = HashSet<Box<[u8]>>;
 
-fn get_known() -> (Known, impl Iterator<Item = Box<[u8]>>) {
-    let mut lines = read_lines(19);
-    let towels = lines.next().unwrap().split(", ").map(|s| (s.as_bytes().into(), true)).collect();
-    let rest = lines.skip(1).map(|s| s.into_boxed_str().into_boxed_bytes());
-    (towels, rest)
+pub fn get_data(input: &str) -> Result<(Vec<&str>, Vec<&str>), ParseError> {
+    let mut lines = Lines::new(19, input);
+    let towels = lines.expect("a list of towels")?.text.split(", ").collect();
+    lines.expect_empty()?;
+    Ok((towels, lines.map(|l| l.text).collect()))
 }
 
-fn get_counts() -> (Towels, impl Iterator<Item = Box<[u8]>>) {
-    let mut lines = read_lines(19);
-    let towels = lines.next().unwrap().split(", ").map(|s| s.as_bytes().into()).collect();
-    let rest = lines.skip(1).map(|s| s.into_boxed_str().into_boxed_bytes());
-    (towels, rest)
+fn get_known(towels: &[&str]) -> Known {
+    towels.iter().map(|s| (s.as_bytes().into(), true)).collect()
 }
 
+fn get_counts(towels: &[&str]) -> Towels { towels.iter().map(|s| s.as_bytes().into()).collect() }
+
 fn check(pattern: &[u8], known: &mut Known) -> bool {
     if let Some(&r) = known.get(pattern) {
         return r;
@@ -48,15 +47,32 @@ fn count(pattern: &[u8], towels: &Towels, counts: &mut Counts) -> usize {
     possibilities
 }
 
-pub fn day19_a() {
-    let (mut known, rest) = get_known();
-    let result = rest.filter(|p| check(p, &mut known)).count();
-    println!("{result}");
+pub fn day19_a((towels, rest): &(Vec<&str>, Vec<&str>)) -> Answer {
+    let mut known = get_known(towels);
+    let result = rest.iter().filter(|p| check(p.as_bytes(), &mut known)).count();
+    result.into()
 }
 
-pub fn day19_b() {
-    let (towels, rest) = get_counts();
+pub fn day19_b((towels, rest): &(Vec<&str>, Vec<&str>)) -> Answer {
+    let towels = get_counts(towels);
     let mut counts = Counts::new();
-    let result: usize = rest.map(|p| count(&p, &towels, &mut counts)).sum();
-    println!("{result}");
+    let result: usize = rest.iter().map(|p| count(p.as_bytes(), &towels, &mut counts)).sum();
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("examples/day19.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day19_a(&get_data(EXAMPLE).unwrap()), Answer::from(6));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day19_b(&get_data(EXAMPLE).unwrap()), Answer::from(16));
+    }
 }
@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
 
-use super::read_lines;
+use super::{Answer, IterArrays as _, Lines, ParseError};
 
 enum Mistake {
     Before,
@@ -44,7 +44,7 @@ fn check_iter_one_mistake(iter: impl Iterator<Item = u32> + Clone) -> bool {
     if (1..=3).contains(&first.abs_diff(*second)) {
         let order = first.cmp(second);
         let mut mistake = Mistake::Before;
-        if test_a.map_windows(|&[l, r]| mistake.check(l, r, order)).all(|b| b) {
+        if test_a.map_windowed(|&[l, r]| mistake.check(l, r, order)).all(|b| b) {
             return true;
         }
     }
@@ -63,22 +63,48 @@ fn check_iter(iter: impl Iterator<Item = u32>) -> bool {
     let second = iter.peek().unwrap();
     if (1..=3).contains(&first.abs_diff(*second)) {
         let order = first.cmp(second);
-        iter.map_windows(|&[l, r]| test_values(l, r, order)).all(|b| b)
+        iter.map_windowed(|&[l, r]| test_values(l, r, order)).all(|b| b)
     } else {
         false
     }
 }
 
-pub fn day2_b() {
-    let result = read_lines(2)
-        .filter(|s| check_iter_one_mistake(s.split_whitespace().map(|s| s.parse::<u32>().unwrap())))
-        .count();
-    println!("{result}");
+pub fn get_reports(input: &str) -> Result<Vec<Vec<u32>>, ParseError> {
+    Lines::new(2, input)
+        .map(|l| {
+            let mut iter = l.text.split_whitespace();
+            let mut report = vec![l.next(&mut iter, "a number")?, l.next(&mut iter, "a number")?];
+            for s in iter {
+                report.push(l.parse(s, "a number")?);
+            }
+            Ok(report)
+        })
+        .collect()
 }
 
-pub fn day2_a() {
-    let result = read_lines(2)
-        .filter(|s| check_iter(s.split_whitespace().map(|s| s.parse::<u32>().unwrap())))
-        .count();
-    println!("{result}");
+pub fn day2_b(reports: &[Vec<u32>]) -> Answer {
+    let result = reports.iter().filter(|r| check_iter_one_mistake(r.iter().copied())).count();
+    result.into()
+}
+
+pub fn day2_a(reports: &[Vec<u32>]) -> Answer {
+    let result = reports.iter().filter(|r| check_iter(r.iter().copied())).count();
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("examples/day2.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day2_a(&get_reports(EXAMPLE).unwrap()), Answer::from(2));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day2_b(&get_reports(EXAMPLE).unwrap()), Answer::from(4));
+    }
 }
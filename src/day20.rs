@@ -1,68 +1,42 @@
-use std::collections::{HashMap, HashSet};
+use super::{Answer, Grid, ParseError, SolveError, Vec2, bfs, find_cell, parse_grid_bytes};
 
-use super::read_grid_bytes;
+type Path = Box<[Vec2<usize>]>;
 
-type Grid<T> = Box<[Box<[T]>]>;
-type Path = Box<[(usize, usize)]>;
+const THRESHOLD: usize = 100;
 
-fn get_maze() -> (Grid<u8>, Grid<usize>, Path) {
-    let grid = read_grid_bytes(20);
-    let (mut x, mut y) = grid
-        .iter()
-        .enumerate()
-        .find_map(|(y, r)| r.iter().position(|&b| b == b'S').map(|x| (x, y)))
-        .unwrap();
-    let mut scores: Box<[Box<[usize]>]> =
-        grid.iter().map(|r| r.iter().map(|_| 0).collect()).collect();
-    let mut path = Vec::from([(x, y)]);
-    while grid[y][x] != b'E' {
-        let current = scores[y][x];
-        for (x_next, y_next) in [(x, y - 1), (x + 1, y), (x, y + 1), (x - 1, y)] {
-            if grid[y_next][x_next] != b'#' && scores[y_next][x_next] == 0 {
-                (x, y) = (x_next, y_next);
-                break;
-            }
-        }
-        scores[y][x] = current + 1;
-        path.push((x, y));
-    }
-    (grid, scores, path.into_boxed_slice())
+pub fn get_track(input: &str) -> Result<Grid<u8>, ParseError> {
+    let grid = parse_grid_bytes(20, input)?;
+    find_cell(20, &grid, b'S', "an `S` cell")?;
+    find_cell(20, &grid, b'E', "an `E` cell")?;
+    Ok(grid)
 }
 
-fn get_possible_saves(grid: &Grid<u8>, scores: &Grid<usize>, path: Path, n: usize) -> usize {
+/// The distance of each cell from the start and the track to the end, or `None` if the end
+/// cannot be reached.
+fn get_maze(grid: &Grid<u8>) -> Option<(Grid<usize>, Path)> {
+    let start = grid.find(&b'S').unwrap();
+    let end = grid.find(&b'E').unwrap();
+    let search = bfs([start], |p| grid.neighbours(p).filter(|&n| grid[n] != b'#'));
+    let scores = Grid::from_fn(grid.width(), grid.height(), |p| search.distance(p).unwrap_or(0));
+    let path = search.path(end)?;
+    Some((scores, path.into_boxed_slice()))
+}
+
+#[expect(clippy::cast_possible_wrap)]
+fn get_possible_saves(
+    grid: &Grid<u8>, scores: &Grid<usize>, path: Path, n: usize, threshold: usize,
+) -> usize {
     let mut possible_saves = 0;
-    for (x, y) in path {
-        let current = scores[y][x];
-        let mut check = |x: usize, y: usize, n| {
-            possible_saves += usize::from(scores[y][x].saturating_sub(current) >= 100 + n);
-        };
-        for o in 1..=n {
-            if x + o < grid[0].len() {
-                check(x + o, y, o);
-            }
-            if let Some(x) = x.checked_sub(o) {
-                check(x, y, o);
-            }
-            if y + o < grid.len() {
-                check(x, y + o, o);
-                for o2 in 1..=n - o {
-                    if x + o2 < grid[0].len() {
-                        check(x + o2, y + o, o + o2);
-                    }
-                    if let Some(x) = x.checked_sub(o2) {
-                        check(x, y + o, o + o2);
-                    }
-                }
-            }
-            if let Some(y) = y.checked_sub(o) {
-                check(x, y, o);
-                for o2 in 1..=n - o {
-                    if x + o2 < grid[0].len() {
-                        check(x + o2, y, o + o2);
-                    }
-                    if let Some(x) = x.checked_sub(o2) {
-                        check(x, y, o + o2);
-                    }
+    let n = n as isize;
+    for p in path {
+        let current = scores[p];
+        for dy in -n..=n {
+            let rest = n - dy.abs();
+            for dx in -rest..=rest {
+                if let Some(cheat) = grid.offset(p, dx, dy) {
+                    let length = dx.unsigned_abs() + dy.unsigned_abs();
+                    possible_saves +=
+                        usize::from(scores[cheat].saturating_sub(current) >= threshold + length);
                 }
             }
         }
@@ -70,14 +44,45 @@ fn get_possible_saves(grid: &Grid<u8>, scores: &Grid<usize>, path: Path, n: usiz
     possible_saves
 }
 
-pub fn day20_a() {
-    let (grid, scores, start) = get_maze();
-    let result = get_possible_saves(&grid, &scores, start, 2);
-    println!("{result}");
+pub fn count_cheats(grid: &Grid<u8>, length: usize, threshold: usize) -> Option<usize> {
+    let (scores, path) = get_maze(grid)?;
+    Some(get_possible_saves(grid, &scores, path, length, threshold))
+}
+
+fn unreachable_end() -> SolveError {
+    SolveError::new(20, "the track does not lead from `S` to `E`")
 }
 
-pub fn day20_b() {
-    let (grid, scores, start) = get_maze();
-    let result = get_possible_saves(&grid, &scores, start, 20);
-    println!("{result}");
+pub fn day20_a(grid: &Grid<u8>) -> Result<Answer, SolveError> {
+    let result = count_cheats(grid, 2, THRESHOLD).ok_or_else(unreachable_end)?;
+    Ok(result.into())
+}
+
+pub fn day20_b(grid: &Grid<u8>) -> Result<Answer, SolveError> {
+    let result = count_cheats(grid, 20, THRESHOLD).ok_or_else(unreachable_end)?;
+    Ok(result.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("examples/day20.txt");
+
+    fn saves(n: usize, threshold: usize) -> usize {
+        count_cheats(&get_track(EXAMPLE).unwrap(), n, threshold).unwrap()
+    }
+
+    #[test]
+    fn part_a() {
+        assert_eq!(saves(2, 1), 44);
+        assert_eq!(saves(2, 64), 1);
+        assert!(day20_a(&get_track("#####\n#S#E#\n#####").unwrap()).is_err());
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(saves(20, 50), 285);
+        assert_eq!(saves(20, 76), 3);
+    }
 }
@@ -1,24 +1,20 @@
 use std::cmp::Ordering;
-use std::collections::{BTreeMap, HashMap};
-use std::fmt::{Display, Formatter, Result as FmtResult, Write};
+use std::collections::HashMap;
 use std::iter;
 
-use super::{Vec2, read_lines};
+use super::{Answer, Direction, IterArrays as _, Lines, ParseError, Vec2};
 
 #[derive(Copy, Clone, Hash, Eq, PartialEq, Debug)]
-enum Move {
-    Up,
-    Right,
-    Down,
-    Left,
+enum Key {
+    Move(Direction),
     Activate,
 }
 
 #[derive(Clone, Hash, Eq, PartialEq, Debug)]
-struct AToA(Box<[Move]>);
+struct AToA(Box<[Key]>);
 
 impl AToA {
-    fn iter(&self) -> impl Iterator<Item = Move> { self.0.iter().copied() }
+    fn iter(&self) -> impl Iterator<Item = Key> { self.0.iter().copied() }
 
     const fn len(&self) -> usize { self.0.len() }
 }
@@ -64,7 +60,9 @@ impl Finder {
             );
             *counts_to_change.entry(a.clone()).or_default() -= isize::try_from(i.count).unwrap();
         }
-        counts_to_change.into_iter().for_each(|(a, c)| self.add(a, c));
+        for (a, c) in counts_to_change {
+            self.add(a, c);
+        }
     }
 
     fn add(&mut self, result: AToA, count: isize) {
@@ -95,20 +93,21 @@ impl Finder {
         }
     }
 
-    const fn arrpad_position(m: Move) -> Vec2<usize> {
-        match m {
-            Move::Up => Vec2::new(1, 0),
-            Move::Activate => Vec2::new(2, 0),
-            Move::Left => Vec2::new(0, 1),
-            Move::Down => Vec2::new(1, 1),
-            Move::Right => Vec2::new(2, 1),
+    const fn arrpad_position(k: Key) -> Vec2<usize> {
+        match k {
+            Key::Move(Direction::North) => Vec2::new(1, 0),
+            Key::Activate => Vec2::new(2, 0),
+            Key::Move(Direction::West) => Vec2::new(0, 1),
+            Key::Move(Direction::South) => Vec2::new(1, 1),
+            Key::Move(Direction::East) => Vec2::new(2, 1),
+            Key::Move(_) => panic!(),
         }
     }
 
     fn shortest_atoa(atoa: &AToA) -> Box<[AToA]> {
-        iter::once(Move::Activate)
+        iter::once(Key::Activate)
             .chain(atoa.iter())
-            .map_windows(|&[l, r]| {
+            .map_windowed(|&[l, r]| {
                 Self::shortest_between(
                     Self::arrpad_position(l),
                     Self::arrpad_position(r),
@@ -121,7 +120,7 @@ impl Finder {
     fn shortest_sequence(sequence: &str) -> Box<[AToA]> {
         iter::once('A')
             .chain(sequence.chars())
-            .map_windows(|&[l, r]| {
+            .map_windowed(|&[l, r]| {
                 Self::shortest_between(
                     Self::numpad_position(l),
                     Self::numpad_position(r),
@@ -134,8 +133,10 @@ impl Finder {
     fn shortest_between(from: Vec2<usize>, to: Vec2<usize>, gap: Vec2<usize>) -> AToA {
         let cmp = to.cmp_each(&from);
         let mut result = Vec::new();
-        let x_m = |o| if o == Ordering::Greater { Move::Right } else { Move::Left };
-        let y_m = |o| if o == Ordering::Greater { Move::Down } else { Move::Up };
+        let x_m =
+            |o| Key::Move(if o == Ordering::Greater { Direction::East } else { Direction::West });
+        let y_m =
+            |o| Key::Move(if o == Ordering::Greater { Direction::South } else { Direction::North });
         match (cmp.x, cmp.y) {
             (Ordering::Equal, Ordering::Equal) => {},
             (Ordering::Equal, c) => result.extend(iter::repeat_n(y_m(c), from.y.abs_diff(to.y))),
@@ -153,8 +154,8 @@ impl Finder {
                     let y = y_m(x_c);
                     // Furthest move has to first
                     match (x, y) {
-                        (Move::Left, _) => horizontal = true,
-                        (_, Move::Down) => horizontal = false,
+                        (Key::Move(Direction::West), _) => horizontal = true,
+                        (_, Key::Move(Direction::South)) => horizontal = false,
                         _ => horizontal = true,
                     }
                 }
@@ -167,24 +168,33 @@ impl Finder {
                 }
             }
         }
-        result.push(Move::Activate);
+        result.push(Key::Activate);
         AToA(result.into_boxed_slice())
     }
 }
 
-fn count(n: usize) -> usize {
+pub fn get_codes(input: &str) -> Result<Vec<&str>, ParseError> {
+    Lines::new(21, input)
+        .map(|l| match l.text.strip_suffix('A') {
+            Some(n) if !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()) => Ok(l.text),
+            _ => Err(l.error(l.text, "a code like `029A`")),
+        })
+        .collect()
+}
+
+pub fn count(codes: &[&str], n: usize) -> usize {
     let mut finder = Finder::new();
-    read_lines(21).map(|s| finder.find(&s, n) * s[..s.len() - 1].parse::<usize>().unwrap()).sum()
+    codes.iter().map(|s| finder.find(s, n) * s[..s.len() - 1].parse::<usize>().unwrap()).sum()
 }
 
-pub fn day21_a() {
-    let result = count(2);
-    println!("{result}");
+pub fn day21_a(codes: &[&str]) -> Answer {
+    let result = count(codes, 2);
+    result.into()
 }
 
-pub fn day21_b() {
-    let result = count(25);
-    println!("{result}");
+pub fn day21_b(codes: &[&str]) -> Answer {
+    let result = count(codes, 25);
+    result.into()
 }
 
 // Furthest first (idk just works)
@@ -201,3 +211,110 @@ pub fn day21_b() {
 // 	
 // <<vA | v<<AA>A^>A | <vA<AA>>^AAvA^A<A>VA^A
 // v<<A | v<A<AA>>^A | <vA
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::Rng;
+
+    const EXAMPLE: &str = include_str!("examples/day21.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day21_a(&get_codes(EXAMPLE).unwrap()), Answer::from(126_384));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day21_b(&get_codes(EXAMPLE).unwrap()), Answer::from(154_115_708_116_294_i64));
+    }
+
+    const NUMPAD: [&[u8]; 4] = [b"789", b"456", b"123", b" 0A"];
+    const ARROWS: [&[u8]; 2] = [b" ^A", b"<v>"];
+
+    fn position(pad: &[&[u8]], key: u8) -> Vec2<usize> {
+        let y = pad.iter().position(|row| row.contains(&key)).unwrap();
+        Vec2::new(pad[y].iter().position(|&k| k == key).unwrap(), y)
+    }
+
+    /// Every shortest walk from `from` to `to` that never crosses the gap.
+    fn walks(pad: &[&[u8]], from: Vec2<usize>, to: Vec2<usize>) -> Vec<Vec<u8>> {
+        if pad[from.y][from.x] == b' ' {
+            return Vec::new();
+        }
+        if from == to {
+            return vec![Vec::new()];
+        }
+        let steps = [
+            (from.x < to.x, b'>', Vec2::new(from.x + 1, from.y)),
+            (from.x > to.x, b'<', Vec2::new(from.x.wrapping_sub(1), from.y)),
+            (from.y < to.y, b'v', Vec2::new(from.x, from.y + 1)),
+            (from.y > to.y, b'^', Vec2::new(from.x, from.y.wrapping_sub(1))),
+        ];
+        let mut result = Vec::new();
+        for (_, key, next) in steps.into_iter().filter(|&(toward, ..)| toward) {
+            for mut walk in walks(pad, next, to) {
+                walk.insert(0, key);
+                result.push(walk);
+            }
+        }
+        result
+    }
+
+    /// Presses on the outermost keypad for `keys` to be typed on `pad`, with `robots` arrow
+    /// keypads in between, trying every walk on every keypad.
+    fn presses(
+        pad: &[&[u8]], keys: &[u8], robots: usize, cache: &mut HashMap<(Vec<u8>, usize), usize>,
+    ) -> usize {
+        if robots == 0 {
+            return keys.len();
+        }
+        if let Some(&count) = cache.get(&(keys.to_vec(), robots)) {
+            return count;
+        }
+        let mut from = position(pad, b'A');
+        let mut count = 0;
+        for &key in keys {
+            let to = position(pad, key);
+            count += walks(pad, from, to)
+                .into_iter()
+                .map(|mut walk| {
+                    walk.push(b'A');
+                    presses(&ARROWS, &walk, robots - 1, cache)
+                })
+                .min()
+                .unwrap();
+            from = to;
+        }
+        cache.insert((keys.to_vec(), robots), count);
+        count
+    }
+
+    fn codes(rng: &mut Rng) -> String {
+        (0..rng.range(1..=5))
+            .map(|_| {
+                (0..3).map(|_| char::from(*rng.pick(b"0123456789"))).collect::<String>() + "A\n"
+            })
+            .collect()
+    }
+
+    #[test]
+    fn count_matches_trying_every_walk() {
+        let mut rng = Rng::new(21);
+        let mut cache = HashMap::new();
+        for _ in 0..100 {
+            let input = codes(&mut rng);
+            let codes = get_codes(&input).unwrap();
+            for robots in 0..=4 {
+                let expected: usize = codes
+                    .iter()
+                    .map(|c| {
+                        let number: usize = c[..3].parse().unwrap();
+                        number * presses(&NUMPAD, c.as_bytes(), robots + 1, &mut cache)
+                    })
+                    .sum();
+                assert_eq!(count(&codes, robots), expected, "{robots} robots, {codes:?}");
+            }
+        }
+    }
+}
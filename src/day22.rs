@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-use super::read_lines;
+use super::{Answer, IterArrays as _, ParseError, parse_numbers};
+
+pub fn get_secrets(input: &str) -> Result<Vec<isize>, ParseError> { parse_numbers(22, input) }
 
 struct Memo {
     memo: HashMap<isize, isize>,
@@ -28,7 +30,7 @@ impl Iterator for NumberIter<'_> {
 impl Memo {
     fn new() -> Self { Self { memo: HashMap::new() } }
 
-    fn iter(&mut self, start: isize) -> NumberIter {
+    const fn iter(&mut self, start: isize) -> NumberIter<'_> {
         NumberIter { memo: self, current: start, n: 0 }
     }
 
@@ -56,8 +58,8 @@ impl ChangesCounter {
     fn consume(&mut self, iter: NumberIter) {
         let mut current = HashMap::new();
         iter.map(|p| p.rem_euclid(10))
-            .map_windows(|&[l, r]| (r - l, r))
-            .map_windows(|&[(a, _), (b, _), (c, _), (d, p)]| {
+            .map_windowed(|&[l, r]| (r - l, r))
+            .map_windowed(|&[(a, _), (b, _), (c, _), (d, p)]| {
                 current.entry([a, b, c, d]).or_insert(p);
             })
             .last();
@@ -69,16 +71,36 @@ impl ChangesCounter {
     fn find_best(self) -> ([isize; 4], isize) { self.counts.into_iter().max_by_key(|(_, v)| *v).unwrap() }
 }
 
-pub fn day22_a() {
+pub fn day22_a(secrets: &[isize]) -> Answer {
     let mut memo = Memo::new();
-    let result: isize = read_lines(22).map(|s| memo.iter(s.parse().unwrap()).last().unwrap()).sum();
-    println!("{result}");
+    let result: isize = secrets.iter().map(|&s| memo.iter(s).last().unwrap()).sum();
+    result.into()
 }
 
-pub fn day22_b() { 
+pub fn day22_b(secrets: &[isize]) -> Answer {
     let mut changes = ChangesCounter::new();
     let mut memo = Memo::new();
-    read_lines(22).for_each(|s| changes.consume(memo.iter(s.parse().unwrap())));
-    let (a, result) = changes.find_best();
-    println!("{a:?}, {result}");
+    for &s in secrets {
+        changes.consume(memo.iter(s));
+    }
+    let (_, result) = changes.find_best();
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("examples/day22.txt");
+    const EXAMPLE_B: &str = include_str!("examples/day22b.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day22_a(&get_secrets(EXAMPLE).unwrap()), Answer::from(37_327_623));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day22_b(&get_secrets(EXAMPLE_B).unwrap()), Answer::from(23));
+    }
 }
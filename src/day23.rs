@@ -1,18 +1,18 @@
 use std::collections::HashMap;
 
-use super::{IterPairs as _, SplitOnceArr as _, read_lines};
+use super::{Answer, IterArrays as _, IterPairs as _, Line, Lines, ParseError};
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Debug)]
 struct Node([u8; 2]);
 
 #[expect(clippy::fallible_impl_from)]
 impl From<&str> for Node {
-    fn from(value: &str) -> Self { Self(value.bytes().array_chunks().next().unwrap()) }
+    fn from(value: &str) -> Self { Self(value.bytes().next_array().unwrap()) }
 }
 
 impl From<Node> for String {
     fn from(value: Node) -> Self {
-        Self::from_utf8(value.0.to_vec()).unwrap()
+        value.0.iter().map(|&b| char::from(b)).collect()
     }
 }
 
@@ -22,7 +22,8 @@ impl Node {
     const fn second(self) -> u8 { self.0[1] }
 }
 
-struct Graph {
+#[derive(Clone)]
+pub struct Graph {
     connections: HashMap<Node, Vec<Node>>,
 }
 
@@ -64,18 +65,18 @@ impl Graph {
     }
 }
 
-fn find_recursive(current: Vec<Node>, potential: Vec<Node>, g: &Graph) -> Vec<Node> {
-    let mut largest = current.clone();
-    for p in potential.clone() {
-        let mut new_current = current.clone();
+fn find_recursive(current: &[Node], potential: &[Node], g: &Graph) -> Vec<Node> {
+    let mut largest = current.to_vec();
+    for &p in potential {
+        let mut new_current = current.to_vec();
         new_current.push(p);
-        let new_potential = potential
+        let new_potential: Vec<_> = potential
             .iter()
             .copied()
             .skip_while(|&p2| p2 <= p)
             .filter(|&p2| new_current.iter().all(|&c| g.connected(c, p2)))
             .collect();
-        let result = find_recursive(new_current, new_potential, g);
+        let result = find_recursive(&new_current, &new_potential, g);
         if result.len() > largest.len() {
             largest = result;
         }
@@ -87,10 +88,9 @@ fn find_largest_clique(mut g: Graph) -> Vec<Node> {
     let nodes = g.sort();
     let mut largest = Vec::new();
     for n in nodes {
-        let current = vec![n];
         let potential: Vec<_> =
             g.connections.get(&n).unwrap().iter().copied().skip_while(|&m| m < n).collect();
-        let result = find_recursive(current, potential, &g);
+        let result = find_recursive(&[n], &potential, &g);
         if result.len() > largest.len() {
             largest = result;
         }
@@ -98,25 +98,49 @@ fn find_largest_clique(mut g: Graph) -> Vec<Node> {
     largest
 }
 
-fn get_graph() -> Graph {
+fn get_node(line: &Line, s: &str) -> Result<Node, ParseError> {
+    if s.len() == 2 && s.bytes().all(|b| b.is_ascii_lowercase()) {
+        Ok(Node::from(s))
+    } else {
+        Err(line.error(s, "a two-letter computer name"))
+    }
+}
+
+pub fn get_graph(input: &str) -> Result<Graph, ParseError> {
     let mut graph = Graph::new();
-    read_lines(23)
-        .map(|s| s.split_once_arr('-').unwrap().map(Node::from))
-        .for_each(|[l, r]| graph.add(l, r));
-    graph
+    for l in Lines::new(23, input) {
+        let [left, right] = l.split_once(l.text, '-', "`-`")?;
+        graph.add(get_node(&l, left)?, get_node(&l, right)?);
+    }
+    Ok(graph)
 }
 
-pub fn day23_a() {
-    let graph = get_graph();
+pub fn day23_a(graph: &Graph) -> Answer {
     let result = graph.find_pairs().count();
-    println!("{result}");
+    result.into()
 }
 
-pub fn day23_b() {
-    let graph = get_graph();
-    let clique = find_largest_clique(graph);
+pub fn day23_b(graph: &Graph) -> Answer {
+    let clique = find_largest_clique(graph.clone());
     let mut strings: Vec<_> = clique.into_iter().map(String::from).collect();
     strings.sort();
     let result = strings.join(",");
-    println!("{result}");
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("examples/day23.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day23_a(&get_graph(EXAMPLE).unwrap()), Answer::from(7));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day23_b(&get_graph(EXAMPLE).unwrap()), Answer::from("co,de,ka,ta"));
+    }
 }
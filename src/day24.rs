@@ -1,28 +1,30 @@
 use std::collections::HashMap;
+use std::{array, iter, mem};
 
-use super::read_lines;
+use super::{Answer, IterArrays as _, Line, Lines, ParseError, SolveError};
 
 #[derive(Debug, Copy, Clone)]
-enum Value {
+pub enum Value {
     Known(bool),
     Unknown(Gate),
 }
 
-type Name = [u8; 3];
+pub type Name = [u8; 3];
+pub type Gates = HashMap<Name, Value>;
 
-fn get_name(s: &str) -> Name { s.bytes().array_chunks().next().unwrap() }
+fn get_name(s: &str) -> Name { s.bytes().next_array().unwrap() }
 
 fn display_name(n: Name) -> String { String::from_utf8(n.to_vec()).unwrap() }
 
 #[derive(Copy, Clone, Debug)]
-struct Gate {
+pub struct Gate {
     left: Name,
     right: Name,
     operation: Operation,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-enum Operation {
+pub enum Operation {
     Xor,
     Or,
     And,
@@ -36,41 +38,92 @@ impl Operation {
             Self::And => left & right,
         }
     }
+
+    /// [`Operation::calculate`] on 64 pairs of bits at once.
+    const fn calculate_lanes(self, left: u64, right: u64) -> u64 {
+        match self {
+            Self::Xor => left ^ right,
+            Self::Or => left | right,
+            Self::And => left & right,
+        }
+    }
 }
 
-fn get_data() -> HashMap<Name, Value> {
-    let mut lines = read_lines(24);
-    let mut result: HashMap<_, _> = lines
-        .by_ref()
-        .take_while(|s| !s.is_empty())
-        .map(|s| {
-            let (name, value) = s.split_once(": ").unwrap();
-            let value = match value {
-                "1" => true,
-                "0" => false,
-                _ => unreachable!(),
-            };
-            (get_name(name), Value::Known(value))
-        })
-        .collect();
-    result.extend(lines.map(|s| {
-        let (gate, name) = s.split_once(" -> ").unwrap();
-        let [left, op, right] = gate.split(' ').next_chunk().unwrap();
-        let operation = match op {
+fn parse_name(line: &Line, s: &str) -> Result<Name, ParseError> {
+    if s.len() == 3 && s.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        Ok(get_name(s))
+    } else {
+        Err(line.error(s, "a three-character wire name"))
+    }
+}
+
+pub fn get_data(input: &str) -> Result<Gates, ParseError> {
+    let mut lines = Lines::new(24, input);
+    let mut result = HashMap::new();
+    // Each gate's line, output and inputs, to check once all wires are known.
+    let mut gates = Vec::new();
+    for l in lines.by_ref().take_while(|l| !l.text.is_empty()) {
+        let [name, value] = l.split_once(l.text, ": ", "`: `")?;
+        let value = match value {
+            "1" => true,
+            "0" => false,
+            _ => return Err(l.error(value, "`0` or `1`")),
+        };
+        result.insert(parse_name(&l, name)?, Value::Known(value));
+    }
+    for l in lines {
+        let [gate, name] = l.split_once(l.text, " -> ", "` -> `")?;
+        let mut parts = gate.split(' ');
+        let left_text = l.take(&mut parts, "a wire name")?;
+        let left = parse_name(&l, left_text)?;
+        let operation = match l.take(&mut parts, "an operation")? {
             "XOR" => Operation::Xor,
             "AND" => Operation::And,
             "OR" => Operation::Or,
-            _ => unreachable!(),
+            op => return Err(l.error(op, "`AND`, `OR` or `XOR`")),
         };
-        (
-            get_name(name),
-            Value::Unknown(Gate { left: get_name(left), operation, right: get_name(right) }),
-        )
-    }));
-    result
+        let right_text = l.take(&mut parts, "a wire name")?;
+        let right = parse_name(&l, right_text)?;
+        l.end(parts)?;
+        result.insert(parse_name(&l, name)?, Value::Unknown(Gate { left, right, operation }));
+        gates.push((l, name, [left_text, right_text]));
+    }
+    for (l, _, inputs) in &gates {
+        if let Some(input) = inputs.iter().find(|s| !result.contains_key(&get_name(s))) {
+            return Err(l.error(input, "a wire that is an input or a gate output"));
+        }
+    }
+    let mut visited = HashMap::new();
+    for (_, name, _) in &gates {
+        if let Some(wire) = find_cycle(&result, get_name(name), &mut visited) {
+            let (l, name, _) = gates.iter().find(|(_, name, _)| get_name(name) == wire).unwrap();
+            return Err(l.error(name, "a gate that does not depend on its own output"));
+        }
+    }
+    Ok(result)
 }
 
-fn get_value(values: &mut HashMap<Name, Value>, name: Name) -> bool {
+/// A wire on a loop among the gates feeding `wire`, if there is one. `visited` holds `false`
+/// for the wires being visited and `true` for those already checked.
+fn find_cycle(gates: &Gates, wire: Name, visited: &mut HashMap<Name, bool>) -> Option<Name> {
+    match visited.get(&wire) {
+        Some(false) => return Some(wire),
+        Some(true) => return None,
+        None => {},
+    }
+    visited.insert(wire, false);
+    if let Value::Unknown(g) = gates[&wire] {
+        for input in [g.left, g.right] {
+            if let Some(wire) = find_cycle(gates, input, visited) {
+                return Some(wire);
+            }
+        }
+    }
+    visited.insert(wire, true);
+    None
+}
+
+fn get_value(values: &mut Gates, name: Name) -> bool {
     match *values.get(&name).unwrap() {
         Value::Known(b) => b,
         Value::Unknown(g) => {
@@ -83,143 +136,251 @@ fn get_value(values: &mut HashMap<Name, Value>, name: Name) -> bool {
     }
 }
 
-fn get_zs(values: &HashMap<Name, Value>) -> Vec<Name> {
+fn get_zs(values: &Gates) -> Vec<Name> {
     let mut result: Vec<_> = values.keys().copied().filter(|&n| n[0] == b'z').collect();
     result.sort_unstable_by(|a, b| b.cmp(a));
     result
 }
 
-pub fn day24_a() {
-    let mut gates = get_data();
+pub fn day24_a(gates: &Gates) -> Answer {
+    let mut gates = gates.clone();
     let zs = get_zs(&gates);
     let mut result = 0i64;
     zs.into_iter()
         .map(|z| get_value(&mut gates, z))
         .for_each(|b| result = (result << 1) | i64::from(b));
-    println!("{result}");
+    result.into()
 }
 
-fn swap_gates(gates: &mut HashMap<Name, Value>, a: Name, b: Name) {
-    println!("Before: {:?}, {:?}", gates.get(&a), gates.get(&b));
-    let value_a = *gates.get(&a).unwrap();
-    let value_b = *gates.get_mut(&b).unwrap();
-    gates.insert(a, value_b);
-    gates.insert(b, value_a);
-    println!("After: {:?}, {:?}", gates.get(&a), gates.get(&b));
+/// How many pairs of gate outputs part B swaps back.
+const SWAPS: usize = 4;
+
+#[derive(Copy, Clone)]
+enum Wire {
+    Unvisited,
+    Visiting,
+    Done(u64),
 }
 
-fn check_if_normal_adder() {
-    let mut gates = get_data();
-    // Layer 12 XOR kth, OR z12
-    swap_gates(&mut gates, get_name("kth"), get_name("z12"));
-    // z26 = x26 and y26 and gsd because zmf = ksd or gsd
-    swap_gates(&mut gates, get_name("gsd"), get_name("z26"));
-    // z32 = layer 32 second AND and tbt second XOR
-    swap_gates(&mut gates, get_name("tbt"), get_name("z32"));
-    // z36 = qnf XOR previous_carry, wrong
-    swap_gates(&mut gates, get_name("vpm"), get_name("qnf"));
-    let x_y: Vec<_> = gates
-        .iter()
-        .filter_map(|(&n, g)| match g {
-            Value::Unknown(Gate {
-                left: [b'x', xn0, xn1],
-                operation: o,
-                right: [b'y', yn0, yn1],
-            }) if xn0 == yn0 && xn1 == yn1 => Some((n, (xn0 - b'0') * 10 + (xn1 - b'0'), *o)),
-            Value::Unknown(Gate {
-                left: [b'y', yn0, yn1],
-                operation: o,
-                right: [b'x', xn0, xn1],
-            }) if xn0 == yn0 && xn1 == yn1 => Some((n, (xn0 - b'0') * 10 + (xn1 - b'0'), *o)),
-            _ => None,
-        })
-        .collect();
-    let mut x_and_y: Vec<_> = x_y
-        .iter()
-        .filter_map(|&(name, idx, op)| (op == Operation::And).then_some((idx, name)))
-        .collect();
-    x_and_y.sort_unstable_by_key(|x| x.0);
-    println!("{x_and_y:?}");
-    let x_and_y: Vec<_> = x_and_y.into_iter().map(|x| x.1).collect();
-    let mut x_xor_y: Vec<_> = x_y
-        .iter()
-        .filter_map(|&(name, idx, op)| (op == Operation::Xor).then_some((idx, name)))
-        .collect();
-    x_xor_y.sort_unstable_by_key(|x| x.0);
-    println!("{x_xor_y:?}");
-    let x_xor_y: Vec<_> = x_xor_y.into_iter().map(|x| x.1).collect();
-    let find_names = |a: Name, b: Name, o: Operation| {
-        gates.iter().find_map(|(&n, g)| match g {
-            Value::Unknown(Gate { left, operation, right })
-                if *operation == o && (*left == a && *right == b || *left == b && *right == a) =>
-                Some(n),
-            _ => None,
-        })
-    };
-    // let find_name = |na: Name, o: Operation| -> Vec<_> {
-    //     gates
-    //         .iter()
-    //         .filter_map(|(&n, g)| match g {
-    //             Value::Unknown(Gate { left, operation, right })
-    //                 if *operation == o && *left == na =>
-    //                 Some((n, *right)),
-    //             Value::Unknown(Gate { left, operation, right })
-    //                 if *operation == o && *right == na =>
-    //                 Some((n, *left)),
-    //             _ => None,
-    //         })
-    //         .collect()
-    // };
-    let mut previous_carry = x_and_y[0];
-    let mut layer = 1;
-    loop {
-        println!("Layer {layer} previous carry: {}", display_name(previous_carry));
-        // if layer == 36 {
-        //     println!("{}", display_name(previous_carry));
-        //     println!("{}", display_name(x_xor_y[layer]));
-        //     let potential = find_name(previous_carry, Operation::Xor);
-        //     for p in potential {
-        //         println!("Gate: {}, other input: {}", display_name(p.0), display_name(p.1));
-        //     }
-        // }
-        let Some(second_xor) = find_names(previous_carry, x_xor_y[layer], Operation::Xor) else {
-            println!("Layer {layer} second xor not found!");
-            break;
-        };
-        let Some(second_and) = find_names(previous_carry, x_xor_y[layer], Operation::And) else {
-            println!("Layer {layer} second and not found!");
-            break;
-        };
-        // if layer == 26 {
-        // if layer == 32 {
-        //     println!("{}", display_name(second_and));
-        //     println!("{}", display_name(second_xor));
-        //     println!("{}", display_name(x_and_y[layer]));
-        //     let potential = find_name(second_and, Operation::Or);
-        //     let potential = find_name(x_and_y[layer], Operation::Or);
-        //     for p in potential {
-        //         println!("Gate: {}, other input: {}", display_name(p.0), display_name(p.1));
-        //     }
-        // }
-        let Some(or) = find_names(x_and_y[layer], second_and, Operation::Or) else {
-            println!("Layer {layer} or not found!");
-            break;
+/// The circuit with numbered wires, so that gate outputs can be swapped and the result tested
+/// quickly.
+struct Adder {
+    names: Vec<Name>,
+    /// The gate driving each wire, or `None` for the inputs.
+    gates: Vec<Option<(usize, Operation, usize)>>,
+    x: Vec<usize>,
+    y: Vec<usize>,
+    z: Vec<usize>,
+    /// The additions to test, each wire carrying one per bit of a `u64`.
+    tests: [(u64, u64); 64],
+}
+
+impl Adder {
+    /// The circuit as an adder, or `None` unless it has `x` and `y` inputs of the same width
+    /// under 64 bits and one more `z` output.
+    fn new(gates: &Gates) -> Option<Self> {
+        let mut names: Vec<_> = gates.keys().copied().collect();
+        names.sort_unstable();
+        let index: HashMap<_, _> = names.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+        let wires = |prefix| -> Vec<_> {
+            names.iter().enumerate().filter(|(_, n)| n[0] == prefix).map(|(i, _)| i).collect()
         };
-        println!(
-            "Layer {layer} - 2nd XOR: {}, 2nd AND: {}, OR: {}",
-            display_name(second_xor),
-            display_name(second_and),
-            display_name(or)
-        );
-        layer += 1;
-        previous_carry = or;
-        if layer > 44 {
-            break;
+        let (x, y, z) = (wires(b'x'), wires(b'y'), wires(b'z'));
+        if x.len() != y.len() || z.len() != x.len() + 1 || x.len() >= 64 {
+            return None;
         }
+        let gates = names
+            .iter()
+            .map(|n| match gates[n] {
+                Value::Known(_) => None,
+                Value::Unknown(g) => Some((index[&g.left], g.operation, index[&g.right])),
+            })
+            .collect();
+        // Carries through every bit, then random additions.
+        let all = (1 << x.len()) - 1;
+        let mut tests = [(all, 1), (all, all), (all, 0), (0, all), (0, 0), (1, all)]
+            .into_iter()
+            .chain(iter::repeat_with({
+                let mut rng = super::utils::Rng::new(24);
+                move || (rng.next_u64() & all, rng.next_u64() & all)
+            }));
+        Some(Self { names, gates, x, y, z, tests: array::from_fn(|_| tests.next().unwrap()) })
+    }
+
+    fn evaluate(&self, wire: usize, values: &mut [Wire]) -> Option<u64> {
+        match values[wire] {
+            Wire::Done(value) => return Some(value),
+            Wire::Visiting => return None,
+            Wire::Unvisited => {},
+        }
+        let (left, operation, right) = self.gates[wire]?;
+        values[wire] = Wire::Visiting;
+        let value =
+            operation.calculate_lanes(self.evaluate(left, values)?, self.evaluate(right, values)?);
+        values[wire] = Wire::Done(value);
+        Some(value)
+    }
+
+    /// Whether outputs up to `z[bit]` are right for the tests, with the input bits above `bit`
+    /// cleared. False if the circuit has a loop.
+    fn adds(&self, bit: usize) -> bool {
+        let mask = (1_u64 << (bit + 1).min(self.x.len())) - 1;
+        let tests = self.tests.map(|(x, y)| (x & mask, y & mask));
+        let mut values = vec![Wire::Unvisited; self.names.len()];
+        for (i, (&x, &y)) in self.x.iter().zip(&self.y).enumerate() {
+            let lanes = |get: fn((u64, u64)) -> u64| {
+                tests.iter().rev().fold(0, |lanes, &test| (lanes << 1) | (get(test) >> i & 1))
+            };
+            values[x] = Wire::Done(lanes(|(x, _)| x));
+            values[y] = Wire::Done(lanes(|(_, y)| y));
+        }
+        (0..=bit).all(|i| {
+            let expected =
+                tests.iter().rev().fold(0, |lanes, &(x, y)| (lanes << 1) | ((x + y) >> i & 1));
+            self.evaluate(self.z[i], &mut values) == Some(expected)
+        })
+    }
+
+    /// The wires feeding `outputs`, including themselves.
+    fn cone(&self, outputs: &[usize]) -> Vec<bool> {
+        let mut seen = vec![false; self.names.len()];
+        let mut stack = outputs.to_vec();
+        while let Some(wire) = stack.pop() {
+            if !mem::replace(&mut seen[wire], true) {
+                stack.extend(self.gates[wire].iter().flat_map(|&(left, _, right)| [left, right]));
+            }
+        }
+        seen
+    }
+
+    /// Gates that may be swapped to fix `z[bit]`: `z[bit]` itself and those that depend only on
+    /// input bits up to `bit` and do not feed the lower outputs, which are already right.
+    fn candidates(&self, bit: usize) -> Vec<usize> {
+        let fixed = self.cone(&self.z[..bit]);
+        let higher: Vec<_> = [&self.x, &self.y]
+            .into_iter()
+            .flat_map(|inputs| inputs.get(bit + 1..).unwrap_or_default())
+            .collect();
+        (0..self.names.len())
+            .filter(|&w| self.gates[w].is_some() && !fixed[w])
+            .filter(|&w| {
+                let cone = self.cone(&[w]);
+                w == self.z[bit] || !higher.iter().any(|&&input| cone[input])
+            })
+            .collect()
+    }
+
+    /// Swaps at most `pairs` pairs of gate outputs so that every output from `z[bit]` up is
+    /// right, trying the swaps that fix the lowest wrong output first. Adds the swapped wires
+    /// to `swapped`.
+    fn repair(&mut self, bit: usize, pairs: usize, swapped: &mut Vec<usize>) -> bool {
+        if bit == self.z.len() {
+            return true;
+        }
+        if self.adds(bit) {
+            return self.repair(bit + 1, pairs, swapped);
+        }
+        if pairs == 0 {
+            return false;
+        }
+        let candidates = self.candidates(bit);
+        for (i, &a) in candidates.iter().enumerate() {
+            for &b in &candidates[i + 1..] {
+                self.gates.swap(a, b);
+                if self.adds(bit) && self.repair(bit + 1, pairs - 1, swapped) {
+                    swapped.extend([a, b]);
+                    return true;
+                }
+                self.gates.swap(a, b);
+            }
+        }
+        false
     }
-    let mut swapped = ["kth", "z12", "gsd", "z26", "tbt", "z32", "vpm", "qnf"];
-    swapped.sort_unstable();
-    println!("{}", swapped.join(","));
 }
 
-pub fn day24_b() { check_if_normal_adder(); }
+/// The wires whose gates must be swapped back for the circuit to add `x` and `y` into `z`.
+///
+/// Every output bit of a ripple-carry adder only depends on the input bits up to it, so the
+/// circuit is fixed from the lowest bit up, testing many additions at once.
+pub fn day24_b(gates: &Gates) -> Result<Answer, SolveError> {
+    let mut adder = Adder::new(gates).ok_or_else(|| {
+        SolveError::new(24, "the circuit does not add two numbers of under 64 bits")
+    })?;
+    let mut swapped = Vec::new();
+    if !adder.repair(0, SWAPS, &mut swapped) {
+        return Err(SolveError::new(24, format!("no {SWAPS} swaps make the circuit add")));
+    }
+    let mut names: Vec<_> = swapped.into_iter().map(|w| display_name(adder.names[w])).collect();
+    names.sort_unstable();
+    Ok(names.join(",").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input_gen::adder;
+    use crate::utils::Rng;
+
+    const EXAMPLE: &str = include_str!("examples/day24.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day24_a(&get_data(EXAMPLE).unwrap()), Answer::from(4));
+    }
+
+    #[test]
+    fn finds_swaps() {
+        let mut rng = Rng::new(24);
+        assert_eq!(day24_b(&get_data(&adder(&mut rng, 45, 0)).unwrap()), Ok(Answer::from("")));
+        for _ in 0..20 {
+            let input = adder(&mut rng, 45, SWAPS);
+            let mut gates = get_data(&input).unwrap();
+            let answer = day24_b(&gates).unwrap().to_string();
+            let mut swapped: Vec<_> = answer.split(',').collect();
+            swapped.dedup();
+            assert_eq!(swapped.len(), 2 * SWAPS, "{answer}");
+            let mut adder = Adder::new(&gates).unwrap();
+            assert!(!adder.adds(45));
+            let mut fixed = Vec::new();
+            assert!(adder.repair(0, SWAPS, &mut fixed));
+            assert!(adder.adds(45));
+            // The swaps also fix the puzzle's own addition.
+            for pair in fixed.chunks(2) {
+                let [a, b] = [pair[0], pair[1]].map(|w| adder.names[w]);
+                let (value_a, value_b) = (gates[&a], gates[&b]);
+                gates.insert(a, value_b);
+                gates.insert(b, value_a);
+            }
+            let number = |prefix| {
+                (0..45).rev().fold(0_i64, |n, bit| {
+                    let Value::Known(b) = gates[&get_name(&format!("{prefix}{bit:02}"))] else {
+                        unreachable!()
+                    };
+                    (n << 1) | i64::from(b)
+                })
+            };
+            assert_eq!(day24_a(&gates), Answer::from(number('x') + number('y')));
+        }
+    }
+
+    #[test]
+    fn reports_unfixable_circuits() {
+        assert!(day24_b(&get_data(EXAMPLE).unwrap()).is_err());
+        let gates = get_data(&adder(&mut Rng::new(24), 45, SWAPS + 1)).unwrap();
+        assert!(day24_b(&gates).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_wiring() {
+        let error = |input| get_data(input).unwrap_err().to_string();
+        assert_eq!(
+            error("x00: 1\n\nx00 AND y00 -> z00"),
+            "day 24 input, line 3, column 9: expected a wire that is an input or a gate output"
+        );
+        assert_eq!(
+            error("x00: 1\n\nx00 AND abc -> z00\nx00 OR z00 -> abc"),
+            "day 24 input, line 3, column 16: expected a gate that does not depend on its own \
+             output"
+        );
+    }
+}
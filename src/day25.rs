@@ -1,34 +1,52 @@
-use super::read_lines;
+use super::{Answer, Line, Lines, ParseError};
 
-type Pins = [usize; 5];
-type Keys = Vec<Pins>;
-type Locks = Vec<Pins>;
+pub type Pins = [usize; 5];
+pub type Keys = Vec<Pins>;
+pub type Locks = Vec<Pins>;
 
-fn get_data() -> (Keys, Locks) {
+fn get_schematic<'a>(lines: &mut Lines<'a>) -> Result<Option<[Line<'a>; 7]>, ParseError> {
+    let Some(first) = lines.find(|l| !l.text.is_empty()) else { return Ok(None) };
+    let mut schematic = [first; 7];
+    for row in &mut schematic[1..] {
+        *row = lines.expect("a row of 5 pins")?;
+    }
+    for row in schematic {
+        if row.text.len() != 5 {
+            return Err(row.error(row.text, "a row of 5 pins"));
+        }
+        if let Some(i) = row.text.bytes().position(|b| b != b'#' && b != b'.') {
+            return Err(row.error(&row.text[i..], "`#` or `.`"));
+        }
+    }
+    Ok(Some(schematic))
+}
+
+pub fn get_data(input: &str) -> Result<(Keys, Locks), ParseError> {
     let mut keys = Vec::new();
     let mut locks = Vec::new();
-    read_lines(25).array_chunks::<8>().for_each(|a| {
+    let mut lines = Lines::new(25, input);
+    while let Some(a) = get_schematic(&mut lines)? {
         let mut result = [0; 5];
-        let check_row = |(s, i): (&String, _)| {
-            s.chars().enumerate().for_each(|(n, c)| {
+        let mut check_row = |(l, i): (&Line, _)| {
+            for (n, c) in l.text.chars().enumerate() {
                 if c == '#' && result[n] < i {
                     result[n] = i;
                 }
-            });
+            }
         };
-        match a[0].as_str() {
+        match a[0].text {
             "#####" => {
-                a[1..=5].iter().rev().zip((1..=5).rev()).for_each(check_row);
+                a[1..=5].iter().rev().zip((1..=5).rev()).for_each(&mut check_row);
                 locks.push(result);
             },
             "....." => {
-                a[1..=5].iter().zip((1..=5).rev()).for_each(check_row);
+                a[1..=5].iter().zip((1..=5).rev()).for_each(&mut check_row);
                 keys.push(result);
             },
-            _ => unreachable!(),
+            _ => return Err(a[0].error(a[0].text, "`#####` or `.....`")),
         }
-    });
-    (keys, locks)
+    }
+    Ok((keys, locks))
 }
 
 fn get_potential(keys: &Keys, locks: &Locks) -> impl Iterator<Item = (Pins, Pins)> {
@@ -41,8 +59,19 @@ fn get_potential(keys: &Keys, locks: &Locks) -> impl Iterator<Item = (Pins, Pins
     })
 }
 
-pub fn day25_a() {
-    let (keys, locks) = get_data();
-    let result = get_potential(&keys, &locks).count();
-    println!("{result}");
+pub fn day25_a((keys, locks): &(Keys, Locks)) -> Answer {
+    let result = get_potential(keys, locks).count();
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("examples/day25.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day25_a(&get_data(EXAMPLE).unwrap()), Answer::from(3));
+    }
 }
@@ -1,4 +1,4 @@
-use super::read_string;
+use super::Answer;
 
 struct ConditionalCounter {
     counter: MulCounter,
@@ -9,14 +9,14 @@ struct ConditionalCounter {
 impl ConditionalCounter {
     const fn new() -> Self { Self { counter: MulCounter::new(), enabled: true, toggle_counter: 0 } }
 
-    fn next(&mut self, c: char) {
+    const fn next(&mut self, c: char) {
         if self.enabled {
             self.counter.next(c);
         }
         self.check_toggle(c);
     }
 
-    fn check_toggle(&mut self, c: char) {
+    const fn check_toggle(&mut self, c: char) {
         match (self.toggle_counter, self.enabled, c) {
             (0, _, 'd') | (1, _, 'o') | (2, true, 'n') | (2, false, '(') | (3, true, '\'') | (4, true, 't') | (5, true, '(') =>
                 self.toggle_counter += 1,
@@ -37,7 +37,7 @@ struct MulCounter {
 impl MulCounter {
     const fn new() -> Self { Self { sum: 0, state: MulState::None } }
 
-    fn next(&mut self, c: char) { self.state.next(c, &mut self.sum); }
+    const fn next(&mut self, c: char) { self.state.next(c, &mut self.sum); }
 }
 
 enum MulState {
@@ -48,7 +48,7 @@ enum MulState {
 }
 
 impl MulState {
-    fn next(&mut self, c: char, sum: &mut usize) {
+    const fn next(&mut self, c: char, sum: &mut usize) {
         match self {
             Self::None =>
                 if c == 'm' {
@@ -82,19 +82,34 @@ impl MulState {
     }
 }
 
-pub fn day3_b() {
-    let content = read_string(3);
+pub fn day3_b(content: &str) -> Answer {
     let mut result = ConditionalCounter::new();
     content.chars().for_each(|c| result.next(c));
     let result = result.counter.sum;
-    println!("{result}");
+    result.into()
 }
 
-pub fn day3_a() {
-    let content = read_string(3);
+pub fn day3_a(content: &str) -> Answer {
     let mut result = MulCounter::new();
     content.chars().for_each(|c| result.next(c));
     let result = result.sum;
-    println!("{result}");
+    result.into()
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_A: &str = include_str!("examples/day3.txt");
+    const EXAMPLE_B: &str = include_str!("examples/day3b.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day3_a(EXAMPLE_A), Answer::from(161));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day3_b(EXAMPLE_B), Answer::from(48));
+    }
+}
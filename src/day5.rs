@@ -1,19 +1,31 @@
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 
-use super::{read_lines, SplitOnceArr};
+use super::{Answer, Line, Lines, ParseError};
 
-pub fn get_rules() -> (HashMap<usize, HashSet<usize>>, impl Iterator<Item = Vec<usize>>) {
-    let mut lines = read_lines(5);
-    let mut rules: HashMap<usize, HashSet<usize>> = HashMap::new();
-    lines.by_ref().take_while(|l| !l.is_empty()).for_each(|i| {
-        let [left, right] = i.split_once_arr('|').unwrap().map(|x| x.parse().unwrap());
+type Rules = HashMap<usize, HashSet<usize>>;
+
+const PAGE: &str = "a page number below 100";
+
+pub fn get_rules(input: &str) -> Result<(Rules, Vec<Vec<usize>>), ParseError> {
+    let mut lines = Lines::new(5, input);
+    let mut rules: Rules = HashMap::new();
+    for l in lines.by_ref().take_while(|l| !l.text.is_empty()) {
+        let [left, right] = l.split_once(l.text, '|', "`|`")?;
+        let [left, right] = [page(&l, left)?, page(&l, right)?];
         rules.entry(right).or_default().insert(left);
-    });
-    (rules, lines.map(|s| s.split(',').map(|s| s.parse().unwrap()).collect()))
+    }
+    let updates = lines
+        .map(|l| l.text.split(',').map(|s| page(&l, s)).collect::<Result<Vec<_>, _>>())
+        .collect::<Result<_, _>>()?;
+    Ok((rules, updates))
 }
 
-fn check_order(numbers: &[usize], rules: &HashMap<usize, HashSet<usize>>) -> bool {
+fn page(line: &Line, s: &str) -> Result<usize, ParseError> {
+    line.parse(s, PAGE).and_then(|n| if n < 100 { Ok(n) } else { Err(line.error(s, PAGE)) })
+}
+
+fn check_order(numbers: &[usize], rules: &Rules) -> bool {
     let mut available = [true; 100];
     for &n in numbers {
         if available[n] {
@@ -25,15 +37,13 @@ fn check_order(numbers: &[usize], rules: &HashMap<usize, HashSet<usize>>) -> boo
     true
 }
 
-pub fn day5_a() {
-    let (rules, lines) = get_rules();
+pub fn day5_a((rules, lines): &(Rules, Vec<Vec<usize>>)) -> Answer {
     let result: usize =
-        lines.filter_map(|l| check_order(&l, &rules).then_some(l[l.len() / 2])).sum();
-    println!("{result}");
+        lines.iter().filter_map(|l| check_order(l, rules).then_some(l[l.len() / 2])).sum();
+    result.into()
 }
 
-pub fn day5_b() {
-    let (rules, lines) = get_rules();
+pub fn day5_b((rules, lines): &(Rules, Vec<Vec<usize>>)) -> Answer {
     let order = |l: &'_ usize, r: &'_ usize| {
         rules
             .get(l)
@@ -41,9 +51,31 @@ pub fn day5_b() {
             .or_else(|| rules.get(r).and_then(|x| x.contains(l).then_some(Ordering::Greater)))
             .unwrap_or(Ordering::Equal)
     };
-    let result: usize = lines.filter(|l| !check_order(l, &rules)).map(|mut l| {
-        l.sort_by(order);
-        l[l.len() / 2]
-    }).sum();
-    println!("{result}");
+    let result: usize = lines
+        .iter()
+        .filter(|l| !check_order(l, rules))
+        .map(|l| {
+            let mut l = l.clone();
+            l.sort_by(order);
+            l[l.len() / 2]
+        })
+        .sum();
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("examples/day5.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day5_a(&get_rules(EXAMPLE).unwrap()), Answer::from(143));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day5_b(&get_rules(EXAMPLE).unwrap()), Answer::from(123));
+    }
 }
@@ -1,59 +1,37 @@
 use std::collections::HashSet;
-use super::read_grid_bytes;
 
-#[derive(Copy, Clone, Hash, Eq, PartialEq)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
-}
+use super::{Answer, Direction, Grid, ParseError, Vec2, find_cell, parse_grid_bytes};
 
-impl Direction {
-    const fn turn(self) -> Self {
-        match self {
-            Self::Up => Self::Right,
-            Self::Right => Self::Down,
-            Self::Down => Self::Left,
-            Self::Left => Self::Up
-        }
-    }
+pub fn get_room(input: &str) -> Result<Grid<u8>, ParseError> {
+    let room = parse_grid_bytes(6, input)?;
+    find_cell(6, &room, b'^', "a `^` cell")?;
+    Ok(room)
 }
 
 #[derive(Copy, Clone, Hash, Eq, PartialEq)]
 struct Position {
-    x: usize,
-    y: usize,
+    cell: Vec2<usize>,
     direction: Direction
 }
 
 impl Position {
-    const fn new(x: usize, y: usize, direction: Direction) -> Self {
-        Self { x, y, direction }
-    }
-    
-    fn forward(&self, room: &[Box<[u8]>], direction: Direction) -> Option<(usize, usize)> {
-        match direction {
-            Direction::Up => (self.y > 0).then(|| (self.x, self.y - 1)),
-            Direction::Right => (self.x + 1 < room[0].len()).then(|| (self.x + 1, self.y)),
-            Direction::Down => (self.y + 1 < room.len()).then(|| (self.x, self.y + 1)),
-            Direction::Left => (self.x > 0).then(|| (self.x - 1, self.y)),
-        }
+    const fn new(cell: Vec2<usize>, direction: Direction) -> Self {
+        Self { cell, direction }
     }
     
-    fn next_position(&self, room: &[Box<[u8]>]) -> Option<Self> {
+    fn next_position(&self, room: &Grid<u8>) -> Option<Self> {
         let mut next_direction = self.direction;
         loop {
-            let (next_x, next_y) = self.forward(room, next_direction)?;
-            if room[next_y][next_x] == b'#' {
-                next_direction = next_direction.turn();
+            let next = room.step(self.cell, next_direction)?;
+            if room[next] == b'#' {
+                next_direction = next_direction.clockwise();
             } else {
-                return Some(Self::new(next_x, next_y, next_direction))
+                return Some(Self::new(next, next_direction))
             }
         }
     }
     
-    fn step(&mut self, room: &[Box<[u8]>]) -> bool {
+    fn step(&mut self, room: &Grid<u8>) -> bool {
         self.next_position(room).inspect(|p| *self = *p).is_some()
     }
 }
@@ -61,7 +39,7 @@ impl Position {
 struct TestGuard<'a> {
     position: Position,
     visited: HashSet<Position>,
-    room: &'a [Box<[u8]>]
+    room: &'a Grid<u8>
 }
 
 impl<'a> TestGuard<'a> {
@@ -83,25 +61,21 @@ impl<'a> TestGuard<'a> {
 struct Guard {
     position: Position,
     counter: usize,
-    room: Box<[Box<[u8]>]>,
+    room: Grid<u8>,
 }
 
 impl Guard {
-    fn new(room: Box<[Box<[u8]>]>) -> Self {
-        let (x, y) = room
-            .iter()
-            .enumerate()
-            .find_map(|(y, r)| r.iter().position(|&b| b == b'^').map(|x| (x, y)))
-            .unwrap();
-        Self { position: Position::new(x, y, Direction::Up), counter: 0, room }
+    fn new(room: Grid<u8>) -> Self {
+        let start = room.find(&b'^').unwrap();
+        Self { position: Position::new(start, Direction::North), counter: 0, room }
     }
     
     fn count_unique(mut self) -> usize {
-        self.room[self.position.y][self.position.x] = b'#';
+        self.room[self.position.cell] = b'+';
         self.counter += 1;
         while self.position.step(&self.room) {
-            if self.room[self.position.y][self.position.x] != b'+' {
-                self.room[self.position.y][self.position.x] = b'+';
+            if self.room[self.position.cell] != b'+' {
+                self.room[self.position.cell] = b'+';
                 self.counter += 1;
             }
         }
@@ -110,11 +84,11 @@ impl Guard {
     
     fn count_possible_loops(mut self) -> usize {
         while let Some(next) = self.position.next_position(&self.room) {
-            if self.room[next.y][next.x] != b'+' {
-                self.room[next.y][next.x] = b'#';
+            if self.room[next.cell] != b'+' {
+                self.room[next.cell] = b'#';
                 let test = TestGuard::new(&self);
                 self.counter += usize::from(test.run());
-                self.room[next.y][next.x] = b'+';
+                self.room[next.cell] = b'+';
             }
             self.position = next;
         }
@@ -122,16 +96,31 @@ impl Guard {
     }
 }
 
-pub fn day6_a() {
-    let room = read_grid_bytes(6);
-    let guard = Guard::new(room);
+pub fn day6_a(room: &Grid<u8>) -> Answer {
+    let guard = Guard::new(room.clone());
     let result = guard.count_unique();
-    println!("{result}");
+    result.into()
 }
 
-pub fn day6_b() {
-    let room = read_grid_bytes(6);
-    let guard = Guard::new(room);
+pub fn day6_b(room: &Grid<u8>) -> Answer {
+    let guard = Guard::new(room.clone());
     let result = guard.count_possible_loops();
-    println!("{result}");
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("examples/day6.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day6_a(&get_room(EXAMPLE).unwrap()), Answer::from(41));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day6_b(&get_room(EXAMPLE).unwrap()), Answer::from(6));
+    }
 }
@@ -1,22 +1,27 @@
-use super::read_lines;
+use super::{Answer, Lines, ParseError};
 
-fn get_values() -> impl Iterator<Item = (u64, Vec<u64>)> {
-    read_lines(7).map(|s| {
-        let (l, r) = s.split_once(": ").unwrap();
-        let target = l.parse().unwrap();
-        let numbers = r.split_whitespace().map(|s| s.parse().unwrap()).collect();
-        (target, numbers)
-    })
+pub fn get_values(input: &str) -> Result<Vec<(u64, Vec<u64>)>, ParseError> {
+    Lines::new(7, input)
+        .map(|l| {
+            let [target, numbers] = l.split_once(l.text, ": ", "`: `")?;
+            let target = l.parse(target, "a number")?;
+            let numbers = numbers
+                .split_whitespace()
+                .map(|s| l.parse(s, "a number"))
+                .collect::<Result<_, _>>()?;
+            Ok((target, numbers))
+        })
+        .collect()
 }
 
-pub fn day7_a() {
-    let result: u64 = get_values().filter_map(|(t, n)| test_a(t, &n).then_some(t)).sum();
-    println!("{result}");
+pub fn day7_a(values: &[(u64, Vec<u64>)]) -> Answer {
+    let result: u64 = values.iter().filter_map(|(t, n)| test_a(*t, n).then_some(t)).sum();
+    result.into()
 }
 
-pub fn day7_b() {
-    let result: u64 = get_values().filter_map(|(t, n)| test_b(t, &n).then_some(t)).sum();
-    println!("{result}");
+pub fn day7_b(values: &[(u64, Vec<u64>)]) -> Answer {
+    let result: u64 = values.iter().filter_map(|(t, n)| test_b(*t, n).then_some(t)).sum();
+    result.into()
 }
 
 fn test_a(target: u64, numbers: &[u64]) -> bool {
@@ -42,3 +47,20 @@ fn test_b_r(target: u64, total: u64, remainder: &[u64]) -> bool {
     let [first, rest @ ..] = remainder else { return total == target };
     test_b_r(target, total * *first, rest) || test_b_r(target, total + *first, rest) || test_b_r(target, concat(total, *first), rest)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("examples/day7.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day7_a(&get_values(EXAMPLE).unwrap()), Answer::from(3749));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day7_b(&get_values(EXAMPLE).unwrap()), Answer::from(11_387));
+    }
+}
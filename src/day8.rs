@@ -1,29 +1,29 @@
 use std::collections::{HashMap, HashSet};
 
-use super::{IterPairs as _, read_grid_bytes};
+use super::{Answer, IterPairs as _, ParseError, parse_grid_bytes};
 
-#[expect(clippy::type_complexity)]
-fn get_antennas() -> (HashMap<u8, Vec<(isize, isize)>>, impl Fn(isize, isize) -> bool) {
-    let grid = read_grid_bytes(8);
+type Antennas = HashMap<u8, Vec<(isize, isize)>>;
+
+#[expect(clippy::cast_possible_wrap)]
+pub fn get_antennas(input: &str) -> Result<(Antennas, (isize, isize)), ParseError> {
+    let grid = parse_grid_bytes(8, input)?;
     let mut antennas: HashMap<u8, Vec<(isize, isize)>> = HashMap::new();
-    for (y, r) in grid.iter().enumerate() {
-        for (x, b) in r.iter().enumerate() {
-            if b.is_ascii_alphanumeric() {
-                #[expect(clippy::cast_possible_wrap)]
-                antennas.entry(*b).or_default().push((x as _, y as _));
-            }
+    for (p, b) in grid.iter() {
+        if b.is_ascii_alphanumeric() {
+            antennas.entry(*b).or_default().push((p.x as _, p.y as _));
         }
     }
-    #[expect(clippy::cast_possible_wrap)]
-    let check_range =
-        move |x, y| (0..grid[0].len() as _).contains(&x) && (0..grid.len() as _).contains(&y);
-    (antennas, check_range)
+    Ok((antennas, (grid.width() as _, grid.height() as _)))
+}
+
+fn in_range((width, height): (isize, isize), x: isize, y: isize) -> bool {
+    (0..width).contains(&x) && (0..height).contains(&y)
 }
 
-pub fn day8_a() {
-    let (antennas, check_range) = get_antennas();
+pub fn day8_a((antennas, size): &(Antennas, (isize, isize))) -> Answer {
+    let check_range = |x, y| in_range(*size, x, y);
     let mut positions = HashSet::new();
-    for v in antennas.into_values() {
+    for v in antennas.values() {
         for (&(x1, y1), &(x2, y2)) in v.iter_pairs() {
             let (xd, yd) = (x2 - x1, y2 - y1);
             let (xa, ya) = (x1 - xd, y1 - yd);
@@ -37,13 +37,13 @@ pub fn day8_a() {
         }
     }
     let result = positions.len();
-    println!("{result}");
+    result.into()
 }
 
-pub fn day8_b() {
-    let (antennas, check_range) = get_antennas();
+pub fn day8_b((antennas, size): &(Antennas, (isize, isize))) -> Answer {
+    let check_range = |x, y| in_range(*size, x, y);
     let mut positions = HashSet::new();
-    for v in antennas.into_values() {
+    for v in antennas.values() {
         for (&(x1, y1), &(x2, y2)) in v.iter_pairs() {
             let (xd, yd) = (x2 - x1, y2 - y1);
             let (mut xp, mut yp) = (x1, y1);
@@ -59,5 +59,22 @@ pub fn day8_b() {
         }
     }
     let result = positions.len();
-    println!("{result}");
-}
\ No newline at end of file
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("examples/day8.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day8_a(&get_antennas(EXAMPLE).unwrap()), Answer::from(14));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day8_b(&get_antennas(EXAMPLE).unwrap()), Answer::from(34));
+    }
+}
@@ -1,7 +1,8 @@
-use super::read_digits;
+use super::{Answer, ParseError, parse_digits};
 
-pub fn day9_a() {
-    let files = read_digits(9);
+pub fn get_files(input: &str) -> Result<Box<[u8]>, ParseError> { parse_digits(9, input) }
+
+pub fn day9_a(files: &[u8]) -> Answer {
     let mut first = 0;
     let mut first_counter = files[first];
     let mut first_id = 0;
@@ -20,6 +21,10 @@ pub fn day9_a() {
         first_counter = files[first];
         while first_counter > 0 {
             if last_counter == 0 {
+                // Every file after this gap has been moved already.
+                if last == first + 1 {
+                    break;
+                }
                 last -= 2;
                 last_counter = files[last];
                 last_id -= 1;
@@ -38,7 +43,7 @@ pub fn day9_a() {
         position += 1;
         last_counter -= 1;
     }
-    println!("{result}");
+    result.into()
 }
 
 #[derive(Copy, Clone)]
@@ -96,8 +101,7 @@ impl Empty {
     }
 }
 
-pub fn day9_b() {
-    let files = read_digits(9);
+pub fn day9_b(files: &[u8]) -> Answer {
     let mut file_slots = Vec::new();
     let mut empty = Vec::new();
     for (i, l) in files.iter().enumerate() {
@@ -120,5 +124,60 @@ pub fn day9_b() {
         f.count(&mut position, &mut result);
         e.count(&mut position, &mut result);
     }
-    println!("{result}");
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use super::*;
+    use crate::utils::Rng;
+
+    const EXAMPLE: &str = include_str!("examples/day9.txt");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day9_a(&get_files(EXAMPLE).unwrap()), Answer::from(1928));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day9_b(&get_files(EXAMPLE).unwrap()), Answer::from(2858));
+    }
+
+    /// Moves the last block into the leftmost gap, one block at a time.
+    fn compact_blocks(files: &[u8]) -> usize {
+        let mut disk: Vec<_> = files
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &l)| iter::repeat_n((i % 2 == 0).then_some(i / 2), l.into()))
+            .collect();
+        loop {
+            while disk.last() == Some(&None) {
+                disk.pop();
+            }
+            let Some(gap) = disk.iter().position(Option::is_none) else { break };
+            disk[gap] = disk.pop().unwrap();
+        }
+        disk.iter().enumerate().map(|(i, id)| i * id.unwrap()).sum()
+    }
+
+    /// A disk map with 1 to 20 files of 1 to 9 blocks and gaps of 0 to 9 blocks.
+    fn disk_map(rng: &mut Rng) -> String {
+        let files = rng.range(1..=20);
+        (0..files * 2 - 1)
+            .map(|i| char::from(*rng.pick(if i % 2 == 0 { b"123456789" } else { b"0123456789" })))
+            .collect()
+    }
+
+    #[test]
+    fn part_a_matches_block_by_block() {
+        let mut rng = Rng::new(9);
+        for _ in 0..500 {
+            let input = disk_map(&mut rng);
+            let files = get_files(&input).unwrap();
+            assert_eq!(day9_a(&files), Answer::from(compact_blocks(&files)), "{input}");
+        }
+    }
 }
@@ -0,0 +1,397 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io::{Read as _, Write as _};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use std::{env, fs, io, str};
+
+use super::utils::input_path;
+
+pub const SESSION_VAR: &str = "AOC_SESSION";
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+pub const CONFIG_FILE: &str = "config.toml";
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub enum FetchError {
+    Io(PathBuf, io::Error),
+    Syntax(PathBuf, usize, &'static str),
+    MissingSession,
+    Url(String, &'static str),
+    Connection(String, io::Error),
+    Curl(String),
+    Response(&'static str),
+    Status(u16, String),
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Io(path, e) => write!(f, "cannot access {}: {e}", path.display()),
+            Self::Syntax(path, line, expected) =>
+                write!(f, "config {}, line {line}: expected {expected}", path.display()),
+            Self::MissingSession =>
+                write!(f, "no session token, set ${SESSION_VAR} or `session` in {CONFIG_FILE}"),
+            Self::Url(url, problem) => write!(f, "base URL `{url}` {problem}"),
+            Self::Connection(host, e) => write!(f, "cannot talk to {host}: {e}"),
+            Self::Curl(message) => write!(f, "{message}"),
+            Self::Response(expected) => write!(f, "malformed HTTP response: expected {expected}"),
+            Self::Status(code, message) => write!(f, "server answered {code}: {message}"),
+        }
+    }
+}
+
+impl Error for FetchError {}
+
+/// Settings read from `<data dir>/config.toml`, each overridden by its environment variable.
+#[derive(Default, Debug, Eq, PartialEq)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, FetchError> {
+        let mut config = match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content)
+                .map_err(|(line, expected)| FetchError::Syntax(path.into(), line, expected))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(e) => return Err(FetchError::Io(path.into(), e)),
+        };
+        if let Ok(session) = env::var(SESSION_VAR) {
+            config.session = Some(session);
+        }
+        if let Ok(base_url) = env::var(BASE_URL_VAR) {
+            config.base_url = Some(base_url);
+        }
+        Ok(config)
+    }
+
+    fn parse(content: &str) -> Result<Self, (usize, &'static str)> {
+        let mut config = Self::default();
+        for (i, line) in content.lines().enumerate().map(|(i, l)| (i + 1, l.trim())) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or((i, "`<key> = \"<value>\"`"))?;
+            let value = value.trim().strip_prefix('"').and_then(|v| v.strip_suffix('"'));
+            let value = Some(value.ok_or((i, "a quoted string"))?.into());
+            match key.trim() {
+                "session" => config.session = value,
+                "base_url" => config.base_url = value,
+                _ => return Err((i, "`session` or `base_url`")),
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// A client for the puzzle site. `https://` base URLs are fetched by running `curl`, while plain
+/// `http://` ones, such as a local mirror, are spoken to directly with a minimal HTTP/1.1 client.
+pub struct Client {
+    transport: Transport,
+    session: String,
+}
+
+enum Transport {
+    Http { host: String, port: u16, prefix: String },
+    Curl { base_url: String },
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: impl Into<String>) -> Result<Self, FetchError> {
+        let error = |problem| FetchError::Url(base_url.into(), problem);
+        let session = session.into();
+        if let Some(rest) = base_url.strip_prefix("https://") {
+            if rest.is_empty() || rest.starts_with(['/', ':']) {
+                return Err(error("has no host"));
+            }
+            let base_url = base_url.trim_end_matches('/').into();
+            return Ok(Self { transport: Transport::Curl { base_url }, session });
+        }
+        let rest = base_url
+            .strip_prefix("http://")
+            .ok_or_else(|| error("is neither `http://` nor `https://`"))?;
+        let (authority, prefix) = rest.find('/').map_or((rest, ""), |i| rest.split_at(i));
+        let (host, port) = match authority.split_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| error("has a bad port"))?),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(error("has no host"));
+        }
+        let prefix = prefix.trim_end_matches('/').into();
+        Ok(Self { transport: Transport::Http { host: host.into(), port, prefix }, session })
+    }
+
+    /// Builds a client from the config in `dir`, for the real site unless it sets a base URL.
+    pub fn from_config(dir: &Path) -> Result<Self, FetchError> {
+        let config = Config::load(&dir.join(CONFIG_FILE))?;
+        let session = config.session.ok_or(FetchError::MissingSession)?;
+        Self::new(config.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL), session)
+    }
+
+    pub fn input(&self, year: usize, day: usize) -> Result<String, FetchError> {
+        let path = format!("/{year}/day/{day}/input");
+        match &self.transport {
+            Transport::Http { host, port, prefix } =>
+                self.get(host, *port, &(prefix.clone() + &path)),
+            Transport::Curl { base_url } => self.curl(&(base_url.clone() + &path)),
+        }
+    }
+
+    fn curl(&self, url: &str) -> Result<String, FetchError> {
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--config", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| FetchError::Curl(format!("cannot run curl: {e}")))?;
+        // The session goes through stdin, as other users can see the command line.
+        let config = format!(
+            "url = \"{url}\"\nheader = \"Cookie: session={}\"\nuser-agent = \"advent_of_code/{}\"\n\
+             max-time = {}\nwrite-out = \"%{{http_code}}\"\n",
+            self.session,
+            env!("CARGO_PKG_VERSION"),
+            TIMEOUT.as_secs(),
+        );
+        let written = child.stdin.take().map_or(Ok(()), |mut s| s.write_all(config.as_bytes()));
+        let output = written
+            .and_then(|()| child.wait_with_output())
+            .map_err(|e| FetchError::Curl(format!("cannot run curl: {e}")))?;
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+            return Err(FetchError::Curl(message));
+        }
+        parse_curl_output(&output.stdout)
+    }
+
+    fn get(&self, host: &str, port: u16, path: &str) -> Result<String, FetchError> {
+        let authority = format!("{host}:{port}");
+        let connection = |e| FetchError::Connection(authority.clone(), e);
+        let mut stream = TcpStream::connect(&authority).map_err(connection)?;
+        stream.set_read_timeout(Some(TIMEOUT)).map_err(connection)?;
+        stream.set_write_timeout(Some(TIMEOUT)).map_err(connection)?;
+        let request = format!(
+            "GET {path} HTTP/1.1\r\nHost: {}\r\nCookie: session={}\r\nUser-Agent: \
+             advent_of_code/{}\r\nConnection: close\r\n\r\n",
+            host,
+            self.session,
+            env!("CARGO_PKG_VERSION"),
+        );
+        stream.write_all(request.as_bytes()).map_err(connection)?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).map_err(connection)?;
+        parse_response(&response)
+    }
+}
+
+fn parse_response(response: &[u8]) -> Result<String, FetchError> {
+    let end = find(response, b"\r\n\r\n").ok_or(FetchError::Response("a header block"))?;
+    let head = str::from_utf8(&response[..end]).map_err(|_| FetchError::Response("headers"))?;
+    let mut lines = head.split("\r\n");
+    let status = lines.next().unwrap_or_default();
+    let code = status
+        .split(' ')
+        .nth(1)
+        .and_then(|c| c.parse().ok())
+        .filter(|_| status.starts_with("HTTP/1."))
+        .ok_or(FetchError::Response("a status line"))?;
+    let headers: Vec<_> =
+        lines.filter_map(|l| l.split_once(':')).map(|(k, v)| (k.trim(), v.trim())).collect();
+    let header = |name: &str| headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name));
+    let mut body = &response[end + 4..];
+    let dechunked;
+    if header("transfer-encoding").is_some_and(|(_, v)| v.eq_ignore_ascii_case("chunked")) {
+        dechunked = dechunk(body).ok_or(FetchError::Response("chunked body"))?;
+        body = &dechunked;
+    } else if let Some((_, length)) = header("content-length") {
+        let length = length.parse().map_err(|_| FetchError::Response("a content length"))?;
+        body = body.get(..length).ok_or(FetchError::Response("the full body"))?;
+    }
+    let body = str::from_utf8(body).map_err(|_| FetchError::Response("a UTF-8 body"))?;
+    answer(code, status.splitn(3, ' ').nth(2).unwrap_or_default(), body)
+}
+
+/// Splits the body from the status code that `curl` writes after it.
+fn parse_curl_output(output: &[u8]) -> Result<String, FetchError> {
+    let (body, code) = output.split_at_checked(output.len().saturating_sub(3)).unwrap_or_default();
+    let code = str::from_utf8(code).ok().and_then(|c| c.parse().ok());
+    let code = code.ok_or(FetchError::Response("a status code"))?;
+    let body = str::from_utf8(body).map_err(|_| FetchError::Response("a UTF-8 body"))?;
+    answer(code, "no details", body)
+}
+
+fn answer(code: u16, reason: &str, body: &str) -> Result<String, FetchError> {
+    if code == 200 {
+        Ok(body.into())
+    } else {
+        let message = body.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or(reason);
+        Err(FetchError::Status(code, message.into()))
+    }
+}
+
+fn dechunk(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut result = Vec::new();
+    loop {
+        let line = find(body, b"\r\n")?;
+        let size = str::from_utf8(&body[..line]).ok()?.split(';').next()?.trim();
+        let size = usize::from_str_radix(size, 16).ok()?;
+        body = &body[line + 2..];
+        if size == 0 {
+            return Some(result);
+        }
+        result.extend_from_slice(body.get(..size)?);
+        body = body.get(size..)?.strip_prefix(b"\r\n")?;
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+pub enum Fetched {
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// Stores the input for `day` of `year` in `dir` unless it is already there. The client is only
+/// built when a download is needed, so cached inputs work without a session token.
+pub fn fetch(
+    dir: &Path, year: usize, day: usize, client: impl FnOnce() -> Result<Client, FetchError>,
+) -> Result<Fetched, FetchError> {
+    let path = input_path(dir, year, day);
+    if path.exists() {
+        return Ok(Fetched::Cached(path));
+    }
+    let input = client()?.input(year, day)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| FetchError::Io(parent.into(), e))?;
+    }
+    let partial = path.with_extension("part");
+    fs::write(&partial, input).map_err(|e| FetchError::Io(partial.clone(), e))?;
+    fs::rename(&partial, &path).map_err(|e| FetchError::Io(path.clone(), e))?;
+    Ok(Fetched::Downloaded(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    use super::*;
+
+    /// Answers `responses` in order, one per connection, and returns the requests it saw.
+    fn serve(responses: &[&'static str]) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/aoc", listener.local_addr().unwrap());
+        let responses = responses.to_vec();
+        let server = thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|response| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut request = Vec::new();
+                    let mut buffer = [0; 1024];
+                    while find(&request, b"\r\n\r\n").is_none() {
+                        let n = stream.read(&mut buffer).unwrap();
+                        request.extend_from_slice(&buffer[..n]);
+                    }
+                    stream.write_all(response.as_bytes()).unwrap();
+                    String::from_utf8(request).unwrap()
+                })
+                .collect()
+        });
+        (url, server)
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("advent_of_code-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn downloads_once() {
+        let (url, server) = serve(&["HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\n1 2\n3\n"]);
+        let dir = temp_dir("downloads_once");
+        let client = || Client::new(&url, "secret");
+        assert!(matches!(fetch(&dir, 2025, 3, client), Ok(Fetched::Downloaded(_))));
+        let Ok(Fetched::Cached(path)) = fetch(&dir, 2025, 3, || Err(FetchError::MissingSession))
+        else {
+            panic!("the second fetch should hit the cache");
+        };
+        assert_eq!(path, dir.join("2025").join("3.txt"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1 2\n3\n");
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /aoc/2025/day/3/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("\r\nCookie: session=secret\r\n"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn responses() {
+        let (url, server) = serve(&[
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nabc\n\r\n2\r\nd\n\r\n0\r\n\
+             \r\n",
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 31\r\n\r\nPlease don't repeatedly\nrequest",
+            "HTTP/1.1 500 Internal Server Error\r\n\r\n",
+        ]);
+        let client = Client::new(&url, "secret").unwrap();
+        assert_eq!(client.input(2024, 1).unwrap(), "abc\nd\n");
+        let error = client.input(2024, 26).unwrap_err();
+        assert!(matches!(error, FetchError::Status(404, m) if m == "Please don't repeatedly"));
+        let error = client.input(2024, 2).unwrap_err();
+        assert!(matches!(error, FetchError::Status(500, m) if m == "Internal Server Error"));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn curl() {
+        assert_eq!(parse_curl_output(b"1 2\n200").unwrap(), "1 2\n");
+        let error = parse_curl_output(b"400").unwrap_err();
+        assert!(matches!(error, FetchError::Status(400, m) if m == "no details"));
+        assert!(matches!(parse_curl_output(b""), Err(FetchError::Response(_))));
+        if Command::new("curl").arg("--version").output().is_err() {
+            return;
+        }
+        let (url, server) = serve(&["HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\n5 6\n"]);
+        let transport = Transport::Curl { base_url: url };
+        let client = Client { transport, session: "secret".into() };
+        assert_eq!(client.input(2024, 7).unwrap(), "5 6\n");
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /aoc/2024/day/7/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("\r\nCookie: session=secret\r\n"));
+    }
+
+    #[test]
+    fn config() {
+        let parsed = Config::parse("# token\nsession = \"abc\"\nbase_url = \"http://x\"\n");
+        assert_eq!(
+            parsed,
+            Ok(Config { session: Some("abc".into()), base_url: Some("http://x".into()) })
+        );
+        assert_eq!(Config::parse("session = abc"), Err((1, "a quoted string")));
+        assert_eq!(Config::parse("token = \"abc\""), Err((1, "`session` or `base_url`")));
+        assert!(Client::new("https://adventofcode.com", "").is_ok());
+        assert!(Client::new("https://", "").is_err());
+        assert!(Client::new("ftp://adventofcode.com", "").is_err());
+        assert!(Client::new("http://:80", "").is_err());
+        if env::var_os(BASE_URL_VAR).is_none() {
+            let dir = temp_dir("config");
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(CONFIG_FILE), "session = \"abc\"\n").unwrap();
+            let client = Client::from_config(&dir).unwrap();
+            let Transport::Curl { base_url } = client.transport else {
+                panic!("the real site needs HTTPS");
+            };
+            assert_eq!(base_url, DEFAULT_BASE_URL);
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+}
@@ -0,0 +1,381 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult, Write as _};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use super::utils::{FIRST_YEAR, Grid, Rng, Vec2, input_path};
+
+/// A random input generator for one day, producing text its `get_*` function accepts.
+pub struct Generator {
+    pub day: usize,
+    /// What `size` counts.
+    pub size: &'static str,
+    /// The size of the official inputs.
+    pub default_size: usize,
+    /// The sizes the generator can make an input of.
+    pub sizes: RangeInclusive<usize>,
+    generate: fn(&mut Rng, usize) -> String,
+}
+
+impl Generator {
+    pub fn generate(&self, seed: u64, size: usize) -> Result<String, GenError> {
+        if !self.sizes.contains(&size) {
+            return Err(GenError::Size(self.day, self.size, self.sizes.clone()));
+        }
+        Ok((self.generate)(&mut Rng::new(seed), size))
+    }
+}
+
+const ANY: RangeInclusive<usize> = 1..=usize::MAX;
+
+pub const GENERATORS: &[Generator] = &[
+    Generator { day: 9, size: "digits", default_size: 19_999, sizes: ANY, generate: disk_map },
+    Generator {
+        day: 12,
+        size: "plots per side",
+        default_size: 140,
+        sizes: ANY,
+        generate: square_farm,
+    },
+    Generator {
+        day: 16,
+        size: "cells per side",
+        default_size: 70,
+        sizes: 2..=usize::MAX,
+        generate: branching_maze,
+    },
+    Generator {
+        day: 20,
+        size: "cells per side",
+        default_size: 70,
+        sizes: 2..=usize::MAX,
+        generate: race_track,
+    },
+    Generator {
+        day: 23,
+        size: "computers",
+        default_size: 520,
+        sizes: 3..=26 * 26,
+        generate: network,
+    },
+    // The 63-bit sum of two 62-bit numbers is the largest that part A's `i64` holds.
+    Generator { day: 24, size: "bits", default_size: 45, sizes: 1..=62, generate: swapped_adder },
+];
+
+fn square_farm(rng: &mut Rng, size: usize) -> String { farm(rng, size, size, 26) }
+
+fn branching_maze(rng: &mut Rng, size: usize) -> String { maze(rng, size, Paths::Many) }
+
+fn race_track(rng: &mut Rng, size: usize) -> String { maze(rng, size, Paths::Single) }
+
+fn network(rng: &mut Rng, size: usize) -> String { graph(rng, size, 13, size.min(13)) }
+
+fn swapped_adder(rng: &mut Rng, size: usize) -> String {
+    adder(rng, size, size.saturating_sub(2).min(4))
+}
+
+pub fn generator(year: usize, day: usize) -> Option<&'static Generator> {
+    (year == FIRST_YEAR).then(|| GENERATORS.iter().find(|g| g.day == day)).flatten()
+}
+
+#[derive(Debug)]
+pub enum GenError {
+    NoGenerator(usize, usize),
+    /// The day, what its size counts and the sizes it supports.
+    Size(usize, &'static str, RangeInclusive<usize>),
+    Exists(PathBuf),
+    Io(PathBuf, io::Error),
+}
+
+impl Display for GenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::NoGenerator(year, day) => write!(f, "no input generator for {year} day {day}"),
+            Self::Size(day, what, sizes) if *sizes.end() == usize::MAX => {
+                write!(f, "day {day} needs at least {} {what}", sizes.start())
+            },
+            Self::Size(day, what, sizes) => {
+                write!(f, "day {day} needs {} to {} {what}", sizes.start(), sizes.end())
+            },
+            Self::Exists(path) => write!(f, "{} already exists", path.display()),
+            Self::Io(path, e) => write!(f, "cannot write {}: {e}", path.display()),
+        }
+    }
+}
+
+impl Error for GenError {}
+
+/// Stores `input` where the `read_*` functions look for it in `dir`, never replacing a file.
+pub fn write(dir: &Path, year: usize, day: usize, input: &str) -> Result<PathBuf, GenError> {
+    let path = input_path(dir, year, day);
+    if path.exists() {
+        return Err(GenError::Exists(path));
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| GenError::Io(parent.into(), e))?;
+    }
+    fs::write(&path, input).map_err(|e| GenError::Io(path.clone(), e))?;
+    Ok(path)
+}
+
+/// A day 9 disk map of `digits` digits, rounded up to end with a file, with files of 1 to 9
+/// blocks and gaps of 0 to 9 blocks.
+pub fn disk_map(rng: &mut Rng, digits: usize) -> String {
+    (0..digits.max(1) | 1)
+        .map(|i| char::from(*rng.pick(if i % 2 == 0 { b"123456789" } else { b"0123456789" })))
+        .collect()
+}
+
+/// A day 12 farm with regions of up to `plants` kinds, grown by mostly copying the plot to the
+/// left or above so that regions have irregular shapes and holes.
+pub fn farm(rng: &mut Rng, width: usize, height: usize, plants: usize) -> String {
+    let kinds = &b"ABCDEFGHIJKLMNOPQRSTUVWXYZ"[..plants.clamp(1, 26)];
+    let mut farm = Grid::filled(width, height, b'A');
+    for p in farm.positions() {
+        farm[p] = match rng.below(8) {
+            0..3 if p.x > 0 => farm[Vec2::new(p.x - 1, p.y)],
+            3..6 if p.y > 0 => farm[Vec2::new(p.x, p.y - 1)],
+            _ => *rng.pick(kinds),
+        };
+    }
+    farm.to_string()
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Paths {
+    /// A single corridor from `S` to `E` without branches, as day 20 requires.
+    Single,
+    /// A maze with dead ends and loops, so there are many routes from `S` to `E`.
+    Many,
+}
+
+/// A day 16 or day 20 maze of `cells` by `cells` junctions, each two tiles apart, with `S` in
+/// the bottom left and `E` in the top right like the official inputs.
+pub fn maze(rng: &mut Rng, cells: usize, paths: Paths) -> String {
+    assert!(cells >= 2, "a maze needs at least two cells per side");
+    let side = 2 * cells + 1;
+    let (start, end) = (Vec2::new(1, side - 2), Vec2::new(side - 2, 1));
+    let mut maze = Grid::filled(side, side, b'#');
+    let mut route = Vec::new();
+    // A depth-first walk carves a spanning tree, so the stack on reaching the end is the only
+    // route to it.
+    let mut stack = vec![start];
+    maze[start] = b'.';
+    while let Some(&cell) = stack.last() {
+        if cell == end {
+            route.clone_from(&stack);
+        }
+        let next: Vec<_> = [(2, 0), (-2, 0), (0, 2), (0, -2)]
+            .into_iter()
+            .filter_map(|(dx, dy)| maze.offset(cell, dx, dy))
+            .filter(|&n| maze[n] == b'#')
+            .collect();
+        if next.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let next = *rng.pick(&next);
+        maze[midpoint(cell, next)] = b'.';
+        maze[next] = b'.';
+        stack.push(next);
+    }
+    match paths {
+        Paths::Single => {
+            maze = Grid::filled(side, side, b'#');
+            for pair in route.windows(2) {
+                maze[pair[0]] = b'.';
+                maze[midpoint(pair[0], pair[1])] = b'.';
+            }
+            maze[end] = b'.';
+        },
+        Paths::Many =>
+            for p in maze.positions() {
+                let inner = (1..side - 1).contains(&p.x) && (1..side - 1).contains(&p.y);
+                if inner && (p.x + p.y) % 2 == 1 && rng.chance(1, 8) {
+                    maze[p] = b'.';
+                }
+            },
+    }
+    maze[start] = b'S';
+    maze[end] = b'E';
+    maze.to_string()
+}
+
+const fn midpoint(a: Vec2<usize>, b: Vec2<usize>) -> Vec2<usize> {
+    Vec2::new(a.x.midpoint(b.x), a.y.midpoint(b.y))
+}
+
+/// A day 23 network of `computers` with a planted clique of `clique` computers.
+///
+/// The other computers are split in two halves, each connected to about `degree` computers of
+/// the other half and to at most one clique member, so no other clique has more than three
+/// computers.
+pub fn graph(rng: &mut Rng, computers: usize, degree: usize, clique: usize) -> String {
+    assert!(computers <= 26 * 26, "there are only {} two-letter names", 26 * 26);
+    assert!((3..=computers).contains(&clique), "the clique must have 3 to {computers} members");
+    let mut names: Vec<_> =
+        (b'a'..=b'z').flat_map(|a| (b'a'..=b'z').map(move |b| [a, b])).collect();
+    rng.shuffle(&mut names);
+    names.truncate(computers);
+    let (members, others) = names.split_at(clique);
+    let (left, right) = others.split_at(others.len() / 2);
+    let mut edges = Vec::new();
+    for (i, &a) in members.iter().enumerate() {
+        edges.extend(members[i + 1..].iter().map(|&b| (a, b)));
+    }
+    for &a in others {
+        if rng.chance(1, 2) {
+            edges.push((a, *rng.pick(members)));
+        }
+    }
+    if !right.is_empty() {
+        for &a in left {
+            let mut neighbours: Vec<_> = (0..degree).map(|_| *rng.pick(right)).collect();
+            neighbours.sort_unstable();
+            neighbours.dedup();
+            edges.extend(neighbours.into_iter().map(|b| (a, b)));
+        }
+    }
+    rng.shuffle(&mut edges);
+    let mut input = String::new();
+    for (a, b) in edges {
+        let (a, b) = if rng.chance(1, 2) { (a, b) } else { (b, a) };
+        input.extend([a[0], a[1], b'-', b[0], b[1], b'\n'].map(char::from));
+    }
+    input
+}
+
+/// A day 24 ripple-carry adder of two `bits`-bit numbers with `swaps` pairs of gate outputs
+/// swapped, each in a different bit like the official inputs.
+///
+/// Bit 0 is a half adder and every other bit a full adder of five gates, the carry out of the
+/// top bit being the extra output bit. The swaps mix up gates of one bit so that the circuit
+/// computes something else and swapping them back is the only fix with that many swaps.
+pub fn adder(rng: &mut Rng, bits: usize, swaps: usize) -> String {
+    assert!((1..64).contains(&bits), "the adder must have 1 to 63 bits");
+    assert!(swaps <= bits.saturating_sub(2), "only bits 1 to {} can have swaps", bits - 2);
+    let mut names: Vec<_> = (b'a'..=b'w')
+        .flat_map(|a| (b'a'..=b'z').flat_map(move |b| (b'a'..=b'z').map(move |c| [a, b, c])))
+        .map(|n| n.map(char::from).iter().collect::<String>())
+        .collect();
+    rng.shuffle(&mut names);
+    let wire = |prefix, bit| format!("{prefix}{bit:02}");
+    // Each bit's gates as [x XOR y, x AND y, sum, sum AND carry, carry out].
+    let mut layers = Vec::new();
+    let mut carry = String::new();
+    for bit in 0..bits {
+        let (x, y) = (wire('x', bit), wire('y', bit));
+        let carry_out = if bit == bits - 1 { wire('z', bits) } else { names.pop().unwrap() };
+        if bit == 0 {
+            layers.push(vec![
+                (x.clone(), "XOR", y.clone(), wire('z', 0)),
+                (x, "AND", y, carry_out.clone()),
+            ]);
+        } else {
+            let (half, both, partial) =
+                (names.pop().unwrap(), names.pop().unwrap(), names.pop().unwrap());
+            layers.push(vec![
+                (x.clone(), "XOR", y.clone(), half.clone()),
+                (x, "AND", y, both.clone()),
+                (half.clone(), "XOR", carry.clone(), wire('z', bit)),
+                (half, "AND", carry, partial.clone()),
+                (both, "OR", partial, carry_out.clone()),
+            ]);
+        }
+        carry = carry_out;
+    }
+    let mut swappable: Vec<_> = (1..bits - 1).collect();
+    rng.shuffle(&mut swappable);
+    for &bit in &swappable[..swaps] {
+        let [a, b] = *rng.pick(&[[0, 1], [2, 1], [2, 3], [2, 4]]);
+        let gates = &mut layers[bit];
+        let (out_a, out_b) = (gates[a].3.clone(), gates[b].3.clone());
+        (gates[a].3, gates[b].3) = (out_b, out_a);
+    }
+    let mut input = String::new();
+    for prefix in ['x', 'y'] {
+        for bit in 0..bits {
+            writeln!(input, "{}: {}", wire(prefix, bit), u8::from(rng.chance(1, 2))).unwrap();
+        }
+    }
+    input.push('\n');
+    let mut gates: Vec<_> = layers.into_iter().flatten().collect();
+    rng.shuffle(&mut gates);
+    for (left, operation, right, out) in gates {
+        let (left, right) = if rng.chance(1, 2) { (left, right) } else { (right, left) };
+        writeln!(input, "{left} {operation} {right} -> {out}").unwrap();
+    }
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::bfs;
+    use crate::{day9, day12, day16, day20, day23, day24};
+
+    #[test]
+    fn generators_parse() {
+        for g in GENERATORS {
+            let input = g.generate(u64::try_from(g.day).unwrap(), g.default_size / 10).unwrap();
+            let parsed = match g.day {
+                9 => day9::get_files(&input).err(),
+                12 => day12::get_farm(&input).err(),
+                16 => day16::get_maze(&input).err(),
+                20 => day20::get_track(&input).err(),
+                23 => day23::get_graph(&input).err(),
+                24 => day24::get_data(&input).err(),
+                _ => unreachable!(),
+            };
+            assert!(parsed.is_none(), "day {}: {}", g.day, parsed.unwrap());
+            assert_eq!(g.generate(1, 5).unwrap(), g.generate(1, 5).unwrap());
+        }
+    }
+
+    #[test]
+    fn size_limits() {
+        for g in GENERATORS {
+            assert!(g.sizes.contains(&g.default_size));
+            let start = *g.sizes.start();
+            assert!(g.generate(0, start).is_ok());
+            assert!(matches!(g.generate(0, start - 1), Err(GenError::Size(..))));
+            if let Some(end) = g.sizes.end().checked_add(1) {
+                assert!(g.generate(0, end - 1).is_ok());
+                assert!(matches!(g.generate(0, end), Err(GenError::Size(..))));
+            }
+        }
+    }
+
+    #[test]
+    fn single_corridor() {
+        let mut rng = Rng::new(20);
+        for cells in 2..20 {
+            let track = day20::get_track(&maze(&mut rng, cells, Paths::Single)).unwrap();
+            let open: Vec<_> = track.positions().filter(|&p| track[p] != b'#').collect();
+            let ends = open
+                .iter()
+                .filter(|&&p| track.neighbours(p).filter(|&n| track[n] != b'#').count() == 1);
+            assert_eq!(ends.count(), 2);
+            let search = bfs([track.find(&b'S').unwrap()], |p| {
+                track.neighbours(p).filter(|&n| track[n] != b'#')
+            });
+            assert_eq!(search.distances().count(), open.len());
+        }
+    }
+
+    #[test]
+    fn planted_clique() {
+        let mut rng = Rng::new(23);
+        let input = graph(&mut rng, 200, 13, 7);
+        let graph = day23::get_graph(&input).unwrap();
+        let answer = day23::day23_b(&graph).to_string();
+        let members: Vec<_> = answer.split(',').collect();
+        assert_eq!(members.len(), 7);
+        for (i, a) in members.iter().enumerate() {
+            for b in &members[i + 1..] {
+                assert!(input.lines().any(|l| l == format!("{a}-{b}") || l == format!("{b}-{a}")));
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+#![warn(clippy::pedantic, clippy::nursery)]
+#![allow(dead_code)]
+#![allow(
+    clippy::must_use_candidate,
+    clippy::return_self_not_must_use,
+    clippy::missing_errors_doc,
+    clippy::missing_panics_doc
+)]
+
+pub mod answers;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod fetch;
+pub mod input_gen;
+mod output;
+pub mod runner;
+pub mod scaffold;
+mod table;
+pub mod utils;
+
+use utils::{
+    Answer, Direction, Grid, IntDivide, IterArrays, IterPairs, Line, Lines, ParseError, Search,
+    SolveError, Vec2, astar, bfs, dijkstra, find_cell, parse_digits, parse_grid_bytes,
+    parse_grid_digits, parse_numbers,
+};
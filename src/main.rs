@@ -1,40 +1,82 @@
-#![feature(
-    impl_trait_in_assoc_type, iter_array_chunks, iter_map_windows, iter_next_chunk, let_chains,
-    pattern
-)]
 #![warn(clippy::pedantic, clippy::nursery)]
-#![allow(dead_code)]
 
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day2;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod utils;
+mod cli;
 
-use utils::{
-    IntDivide, IterPairs, SplitOnceArr, Vec2, read_digits, read_grid_bytes, read_grid_digits,
-    read_lines, read_numbers, read_string,
-};
+use std::error::Error;
+use std::path::Path;
+use std::process::ExitCode;
+use std::{env, io};
 
-fn main() { day25::day25_a() }
+use advent_of_code::day17::{self, Computer};
+use advent_of_code::fetch::{self, Client, Fetched};
+use advent_of_code::input_gen::{self, GenError};
+use advent_of_code::utils::CountingAllocator;
+use advent_of_code::{runner, scaffold, utils};
+use cli::{Cli, Command, USAGE};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn main() -> ExitCode {
+    let cli = match Cli::parse(env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("error: {e}\n{USAGE}");
+            return ExitCode::FAILURE;
+        },
+    };
+    utils::set_input(cli.input);
+    match execute(cli.command, &cli.data_dir, &cli.answers) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        },
+    }
+}
+
+fn execute(command: Command, data_dir: &Path, answers: &Path) -> Result<(), Box<dyn Error>> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    match command {
+        Command::Run { target, timed, record, format } =>
+            runner::run(&target, format, timed, answers, record)?,
+        Command::All { year, record, jobs, format } =>
+            runner::all(year, answers, record, jobs, format)?,
+        Command::Bench { target, repeat } => runner::bench(&target, repeat)?,
+        Command::Check { target } => runner::check(&target, answers)?,
+        Command::Watch { year, day } =>
+            runner::watch(year, day, &scaffold::module_dir(root, year.number).join("examples"))?,
+        Command::Fetch { year, day } =>
+            match fetch::fetch(data_dir, year, day, || Client::from_config(data_dir))? {
+                Fetched::Cached(path) => println!("cached {}", path.display()),
+                Fetched::Downloaded(path) => println!("downloaded {}", path.display()),
+            },
+        Command::New { year, day } =>
+            for path in scaffold::new_day(root, year, day)? {
+                println!("created {}", path.display());
+            },
+        Command::Gen { year, day, size, seed } => {
+            let generator =
+                input_gen::generator(year, day).ok_or(GenError::NoGenerator(year, day))?;
+            let size = size.unwrap_or(generator.default_size);
+            let path = input_gen::write(data_dir, year, day, &generator.generate(seed, size)?)?;
+            println!("generated {} with {size} {}", path.display(), generator.size);
+        },
+        Command::Debug { year } => {
+            let computer = Computer::parse(&utils::read_string(year, 17)?)?;
+            day17::debug::repl(computer, io::stdin().lock(), io::stdout().lock())?;
+        },
+        Command::Trace { year, limit } => {
+            let (steps, end) = Computer::parse(&utils::read_string(year, 17)?)?.trace(limit);
+            for step in steps {
+                println!("{step}");
+            }
+            println!("{end}");
+        },
+        Command::Brute { year, count, jobs } => {
+            let computer = Computer::parse(&utils::read_string(year, 17)?)?;
+            print!("{}", day17::compile::benchmark(&computer, count, jobs));
+        },
+    }
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use super::runner::Part;
+use super::table::Table;
+use super::utils::Answer;
+
+/// How one part went, for the machine-readable formats.
+pub struct Record {
+    pub day: usize,
+    pub part: Part,
+    /// The answer, or the message of the parse error or panic that prevented it.
+    pub answer: Result<Answer, String>,
+    pub parse: Option<Duration>,
+    pub solve: Option<Duration>,
+    pub peak: Option<usize>,
+    pub status: &'static str,
+}
+
+pub fn format_duration(duration: Duration) -> String { format!("{duration:.2?}") }
+
+#[expect(clippy::cast_precision_loss)]
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => write!(json, "\\u{:04x}", u32::from(c)).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn json_or_null<T>(value: Option<T>, f: impl FnOnce(T) -> String) -> String {
+    value.map_or_else(|| "null".into(), f)
+}
+
+/// A JSON array with one object per line, times in nanoseconds and the peak in bytes.
+pub fn json(year: usize, records: &[Record]) -> String {
+    let objects: Vec<_> = records
+        .iter()
+        .map(|r| {
+            let (answer, error) = match &r.answer {
+                Ok(Answer::Number(n)) => (n.to_string(), None),
+                Ok(Answer::Text(s)) => (json_string(s), None),
+                Err(e) => ("null".into(), Some(e)),
+            };
+            format!(
+                "  {{\"year\": {year}, \"day\": {}, \"part\": \"{}\", \"answer\": {answer}, \
+                 \"error\": {}, \"parse_ns\": {}, \"solve_ns\": {}, \"peak_bytes\": {}, \
+                 \"status\": \"{}\"}}",
+                r.day,
+                r.part,
+                json_or_null(error, |e| json_string(e)),
+                json_or_null(r.parse, |d| d.as_nanos().to_string()),
+                json_or_null(r.solve, |d| d.as_nanos().to_string()),
+                json_or_null(r.peak, |b| b.to_string()),
+                r.status,
+            )
+        })
+        .collect();
+    if objects.is_empty() { "[]\n".into() } else { format!("[\n{}\n]\n", objects.join(",\n")) }
+}
+
+pub fn markdown(records: &[Record]) -> String {
+    let mut table =
+        Table::new(&["Day", "Part", "Answer", "Parse", "Solve", "Peak memory", "Status"]);
+    for r in records {
+        let optional = |value: Option<String>| value.unwrap_or_else(|| "-".into());
+        table.push(vec![
+            r.day.to_string(),
+            r.part.to_string(),
+            r.answer.as_ref().map_or_else(Clone::clone, Answer::to_string),
+            optional(r.parse.map(format_duration)),
+            optional(r.solve.map(format_duration)),
+            optional(r.peak.map(format_bytes)),
+            r.status.into(),
+        ]);
+    }
+    table.markdown().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 1,
+                part: Part::A,
+                answer: Ok(Answer::Number(42)),
+                parse: Some(Duration::from_micros(5)),
+                solve: Some(Duration::from_millis(2)),
+                peak: Some(3 << 20),
+                status: "ok",
+            },
+            Record {
+                day: 1,
+                part: Part::B,
+                answer: Err("no \"x\" | y".into()),
+                parse: None,
+                solve: None,
+                peak: None,
+                status: "panic",
+            },
+        ]
+    }
+
+    #[test]
+    fn json_records() {
+        assert_eq!(
+            json(2024, &records()),
+            "[\n  {\"year\": 2024, \"day\": 1, \"part\": \"a\", \"answer\": 42, \"error\": null, \
+             \"parse_ns\": 5000, \"solve_ns\": 2000000, \"peak_bytes\": 3145728, \
+             \"status\": \"ok\"},\n  {\"year\": 2024, \"day\": 1, \"part\": \"b\", \
+             \"answer\": null, \"error\": \"no \\\"x\\\" | y\", \"parse_ns\": null, \
+             \"solve_ns\": null, \"peak_bytes\": null, \"status\": \"panic\"}\n]\n"
+        );
+        assert_eq!(json_string("a\tb\\"), "\"a\\u0009b\\\\\"");
+        assert_eq!(json(2024, &[]), "[]\n");
+    }
+
+    #[test]
+    fn markdown_table() {
+        assert_eq!(
+            markdown(&records()),
+            "\
+| Day | Part | Answer      | Parse  | Solve  | Peak memory | Status |
+| --- | ---- | ----------- | ------ | ------ | ----------- | ------ |
+| 1   | a    | 42          | 5.00µs | 2.00ms | 3.0 MiB     | ok     |
+| 1   | b    | no \"x\" \\| y | -      | -      | -           | panic  |
+"
+        );
+    }
+}
@@ -0,0 +1,556 @@
+use std::any::Any;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use std::{fs, io};
+
+use super::answers::{Answers, StoreError};
+use super::day17::Computer;
+use super::output::{self, Record, format_duration};
+use super::table::Table;
+use super::utils::{
+    Answer, PartError, SolveError, Watcher, input, measure, parallel_map, read_string,
+};
+use super::{
+    day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13, day14, day15,
+    day16, day17, day18, day19, day20, day21, day22, day23, day24, day25,
+};
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum Part {
+    A,
+    B,
+}
+
+impl Part {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "a" | "A" => Some(Self::A),
+            "b" | "B" => Some(Self::B),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::A => write!(f, "a"),
+            Self::B => write!(f, "b"),
+        }
+    }
+}
+
+/// How `run` and `all` print their results.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Format {
+    Human,
+    Json,
+    Markdown,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "human" => Some(Self::Human),
+            "json" => Some(Self::Json),
+            "markdown" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RunError {
+    Part(PartError),
+    Input(io::Error),
+    Store(StoreError),
+    Mismatch(usize),
+    Failed(usize),
+    Watch(io::Error),
+}
+
+impl Display for RunError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Part(e) => write!(f, "{e}"),
+            Self::Input(e) => write!(f, "{e}"),
+            Self::Store(e) => write!(f, "{e}"),
+            Self::Mismatch(1) => write!(f, "1 answer differs from the stored one"),
+            Self::Mismatch(n) => write!(f, "{n} answers differ from the stored ones"),
+            Self::Failed(1) => write!(f, "1 part failed"),
+            Self::Failed(n) => write!(f, "{n} parts failed"),
+            Self::Watch(e) => write!(f, "cannot watch for changes: {e}"),
+        }
+    }
+}
+
+impl Error for RunError {}
+
+impl From<PartError> for RunError {
+    fn from(value: PartError) -> Self { Self::Part(value) }
+}
+
+impl From<StoreError> for RunError {
+    fn from(value: StoreError) -> Self { Self::Store(value) }
+}
+
+pub struct Solution {
+    pub answer: Answer,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+impl Solution {
+    pub fn total(&self) -> Duration { self.parse + self.solve }
+}
+
+pub struct Bench {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+pub type Solver = fn(&str) -> Result<Solution, PartError>;
+
+/// What a solve function returns: the answer, or an error for an input that parses but has
+/// no answer.
+pub trait IntoAnswer {
+    fn into_answer(self) -> Result<Answer, SolveError>;
+}
+
+impl IntoAnswer for Answer {
+    fn into_answer(self) -> Result<Answer, SolveError> { Ok(self) }
+}
+
+impl IntoAnswer for Result<Answer, SolveError> {
+    fn into_answer(self) -> Result<Answer, SolveError> { self }
+}
+
+pub fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+macro_rules! part {
+    ($solve:path) => {
+        |input: &str| {
+            let (answer, solve) = time(|| $solve(input));
+            Ok(Solution { answer: answer.into_answer()?, parse: Duration::ZERO, solve })
+        }
+    };
+    ($parse:path, $solve:path) => {
+        |input: &str| {
+            let (parsed, parse) = time(|| $parse(input));
+            let parsed = parsed?;
+            let (answer, solve) = time(|| $solve(&parsed));
+            Ok(Solution { answer: answer.into_answer()?, parse, solve })
+        }
+    };
+}
+
+pub struct Day {
+    pub number: usize,
+    a: Solver,
+    b: Option<Solver>,
+}
+
+impl Day {
+    const fn new(number: usize, a: Solver, b: Solver) -> Self { Self { number, a, b: Some(b) } }
+
+    const fn single(number: usize, a: Solver) -> Self { Self { number, a, b: None } }
+
+    pub const fn part(&self, part: Part) -> Option<Solver> {
+        match part {
+            Part::A => Some(self.a),
+            Part::B => self.b,
+        }
+    }
+
+    pub fn parts(&self) -> impl Iterator<Item = (Part, Solver)> {
+        [Part::A, Part::B].into_iter().filter_map(|p| self.part(p).map(|f| (p, f)))
+    }
+
+    fn selected(&self, part: Option<Part>) -> impl Iterator<Item = (Part, Solver)> {
+        self.parts().filter(move |&(p, _)| part.is_none_or(|part| part == p))
+    }
+
+    pub fn bench(
+        &self, year: usize, part: Option<Part>, repeat: usize,
+    ) -> Result<Vec<(Part, Bench)>, RunError> {
+        let input = read_string(year, self.number).map_err(RunError::Input)?;
+        self.selected(part)
+            .map(|(p, f)| {
+                let mut times = (0..repeat)
+                    .map(|_| f(&input).map(|s| s.total()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                times.sort_unstable();
+                let bench = Bench {
+                    min: times[0],
+                    median: times[times.len() / 2],
+                    max: times[times.len() - 1],
+                };
+                Ok((p, bench))
+            })
+            .collect()
+    }
+}
+
+pub struct Year {
+    pub number: usize,
+    pub days: &'static [Day],
+}
+
+impl Year {
+    pub fn day(&self, day: usize) -> Option<&'static Day> {
+        self.days.iter().find(|d| d.number == day)
+    }
+}
+
+pub enum Target {
+    Day { year: &'static Year, day: &'static Day, part: Option<Part> },
+    All(&'static Year),
+}
+
+impl Target {
+    pub const fn year(&self) -> &'static Year {
+        match *self {
+            Self::Day { year, .. } | Self::All(year) => year,
+        }
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = (&'static Day, Option<Part>)> {
+        let (days, part): (&'static [Day], _) = match *self {
+            Self::Day { day, part, .. } => (std::slice::from_ref(day), part),
+            Self::All(year) => (year.days, None),
+        };
+        days.iter().map(move |d| (d, part))
+    }
+}
+
+fn status(stored: Option<&Answer>, answer: &Answer) -> &'static str {
+    match stored {
+        None => "new",
+        Some(s) if s == answer => "ok",
+        Some(_) => "mismatch",
+    }
+}
+
+pub fn run(
+    target: &Target, format: Format, timed: bool, path: &Path, record: bool,
+) -> Result<(), RunError> {
+    // The machine-readable formats say whether each answer matches the stored one.
+    let mut answers =
+        (record || format != Format::Human).then(|| Answers::load(path)).transpose()?;
+    let year = target.year().number;
+    let mut table = Table::new(&["Day", "Part", "Answer", "Parse", "Solve"]);
+    let mut records = Vec::new();
+    for (day, part) in target.days() {
+        let input = read_string(year, day.number).map_err(RunError::Input)?;
+        for (p, f) in day.selected(part) {
+            let (solution, peak) = measure(|| f(&input));
+            let solution = solution?;
+            let stored = answers.as_ref().and_then(|a| a.get(year, day.number, p));
+            let status = status(stored, &solution.answer);
+            if let Some(answers) = answers.as_mut().filter(|_| record) {
+                answers.insert(year, day.number, p, solution.answer.clone());
+            }
+            match format {
+                Format::Human if timed => table.push(vec![
+                    day.number.to_string(),
+                    p.to_string(),
+                    solution.answer.to_string(),
+                    format_duration(solution.parse),
+                    format_duration(solution.solve),
+                ]),
+                Format::Human => println!("Day {} {p}: {}", day.number, solution.answer),
+                Format::Json | Format::Markdown => records.push(Record {
+                    day: day.number,
+                    part: p,
+                    answer: Ok(solution.answer),
+                    parse: Some(solution.parse),
+                    solve: Some(solution.solve),
+                    peak,
+                    status,
+                }),
+            }
+        }
+    }
+    match format {
+        Format::Human if timed => print!("{table}"),
+        Format::Human => {},
+        Format::Json => print!("{}", output::json(year, &records)),
+        Format::Markdown => print!("{}", output::markdown(&records)),
+    }
+    if let Some(answers) = answers.filter(|_| record) {
+        answers.save(path)?;
+    }
+    Ok(())
+}
+
+pub fn check(target: &Target, path: &Path) -> Result<(), RunError> {
+    let answers = Answers::load(path)?;
+    let mut table = Table::new(&["Day", "Part", "Status", "Stored", "Current"]);
+    let mut mismatches = 0;
+    let year = target.year().number;
+    for (day, part) in target.days() {
+        let input = read_string(year, day.number).map_err(RunError::Input)?;
+        for (p, f) in day.selected(part) {
+            let current = f(&input)?.answer;
+            let stored = answers.get(year, day.number, p);
+            let status = match stored {
+                None => "new",
+                Some(s) if *s == current => "ok",
+                Some(_) => {
+                    mismatches += 1;
+                    "changed"
+                },
+            };
+            table.push(vec![
+                day.number.to_string(),
+                p.to_string(),
+                status.into(),
+                stored.map_or_else(|| "-".into(), Answer::to_string),
+                current.to_string(),
+            ]);
+        }
+    }
+    print!("{table}");
+    if mismatches == 0 { Ok(()) } else { Err(RunError::Mismatch(mismatches)) }
+}
+
+pub fn bench(target: &Target, repeat: usize) -> Result<(), RunError> {
+    let mut table = Table::new(&["Day", "Part", "Min", "Median", "Max"]);
+    for (day, part) in target.days() {
+        for (p, bench) in day.bench(target.year().number, part, repeat)? {
+            table.push(vec![
+                day.number.to_string(),
+                p.to_string(),
+                format_duration(bench.min),
+                format_duration(bench.median),
+                format_duration(bench.max),
+            ]);
+        }
+    }
+    print!("{table}");
+    Ok(())
+}
+
+enum Outcome {
+    Solved(Solution),
+    /// The input could not be read or has no answer.
+    Invalid(String),
+    Panicked(String),
+}
+
+struct Report {
+    day: usize,
+    part: Part,
+    outcome: Outcome,
+    elapsed: Duration,
+    peak: Option<usize>,
+}
+
+impl Report {
+    fn run(year: usize, day: usize, part: Part, f: Solver) -> Self {
+        let (result, elapsed) = time(|| {
+            panic::catch_unwind(|| {
+                let input = read_string(year, day).map_err(|e| e.to_string())?;
+                Ok(measure(|| f(&input)))
+            })
+        });
+        let (outcome, peak) = match result {
+            Ok(Ok((Ok(solution), peak))) => (Outcome::Solved(solution), peak),
+            Ok(Ok((Err(e), peak))) => (Outcome::Invalid(e.to_string()), peak),
+            Ok(Err(e)) => (Outcome::Invalid(e), None),
+            Err(payload) => (Outcome::Panicked(panic_message(payload.as_ref())), None),
+        };
+        Self { day, part, outcome, elapsed, peak }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic");
+    message.lines().next().unwrap_or_default().into()
+}
+
+pub fn all(
+    year: &Year, path: &Path, record: bool, jobs: usize, format: Format,
+) -> Result<(), RunError> {
+    let mut answers = Answers::load(path)?;
+    let parts: Vec<_> =
+        year.days.iter().flat_map(|d| d.parts().map(move |(p, f)| (d.number, p, f))).collect();
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let reports =
+        parallel_map(&parts, jobs, |&(day, part, f)| Report::run(year.number, day, part, f));
+    panic::set_hook(hook);
+    let mut table = Table::new(&["Day", "Part", "Answer", "Elapsed", "Status"]);
+    let mut records = Vec::new();
+    let mut failures = 0;
+    for report in reports {
+        let (answer, status, times) = match report.outcome {
+            Outcome::Solved(solution) => {
+                let stored = answers.get(year.number, report.day, report.part);
+                let status = status(stored, &solution.answer);
+                if record {
+                    answers.insert(year.number, report.day, report.part, solution.answer.clone());
+                }
+                (Ok(solution.answer), status, Some((solution.parse, solution.solve)))
+            },
+            Outcome::Invalid(e) => (Err(e), "error", None),
+            Outcome::Panicked(message) => (Err(message), "panic", None),
+        };
+        if !matches!(status, "ok" | "new") {
+            failures += 1;
+        }
+        table.push(vec![
+            report.day.to_string(),
+            report.part.to_string(),
+            answer.as_ref().map_or_else(Clone::clone, Answer::to_string),
+            format_duration(report.elapsed),
+            status.into(),
+        ]);
+        records.push(Record {
+            day: report.day,
+            part: report.part,
+            answer,
+            parse: times.map(|(parse, _)| parse),
+            solve: times.map(|(_, solve)| solve),
+            peak: report.peak,
+            status,
+        });
+    }
+    match format {
+        Format::Human => print!("{table}"),
+        Format::Json => print!("{}", output::json(year.number, &records)),
+        Format::Markdown => print!("{}", output::markdown(&records)),
+    }
+    if record {
+        answers.save(path)?;
+    }
+    if failures == 0 { Ok(()) } else { Err(RunError::Failed(failures)) }
+}
+
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Runs both parts of `day` on its input and on every example fixture in `examples`, then again
+/// each time one of those files changes. Panics and parse errors are reported, not fatal.
+pub fn watch(year: &Year, day: &Day, examples: &Path) -> Result<(), RunError> {
+    let mut files: Vec<_> = input().path(year.number, day.number).into_iter().collect();
+    files.extend(fixtures(examples, day.number));
+    let mut watcher = Watcher::new(&files).map_err(RunError::Watch)?;
+    loop {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let mut table = Table::new(&["File", "Part", "Answer", "Parse", "Solve"]);
+        for file in &files {
+            let name = file.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            let content = fs::read_to_string(file);
+            for (p, f) in day.parts() {
+                let (answer, times) = match &content {
+                    Ok(content) => match panic::catch_unwind(|| f(content)) {
+                        Ok(Ok(solution)) => (
+                            solution.answer.to_string(),
+                            [format_duration(solution.parse), format_duration(solution.solve)],
+                        ),
+                        Ok(Err(e)) => (e.to_string(), Default::default()),
+                        Err(payload) => (panic_message(payload.as_ref()), Default::default()),
+                    },
+                    Err(e) => (e.to_string(), Default::default()),
+                };
+                let [parse, solve] = times;
+                table.push(vec![name.clone(), p.to_string(), answer, parse, solve]);
+            }
+        }
+        panic::set_hook(hook);
+        print!("{table}");
+        let changed = watcher.wait(DEBOUNCE).map_err(RunError::Watch)?;
+        let names: Vec<_> = changed.iter().map(|f| f.display().to_string()).collect();
+        println!("\nchanged {}", names.join(", "));
+    }
+}
+
+/// Example files for `day`, such as `day3.txt` and `day3b.txt` but not `day31.txt`.
+fn fixtures(examples: &Path, day: usize) -> Vec<PathBuf> {
+    let prefix = format!("day{day}");
+    let mut fixtures: Vec<_> = fs::read_dir(examples)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let stem = path.file_stem()?.to_str()?.strip_prefix(&prefix)?;
+            let fixture = path.extension().is_some_and(|e| e == "txt")
+                && !stem.starts_with(|c: char| c.is_ascii_digit());
+            fixture.then_some(path)
+        })
+        .collect();
+    fixtures.sort();
+    fixtures
+}
+
+pub const YEARS: &[Year] = &[Year { number: 2024, days: DAYS_2024 }];
+
+const DAYS_2024: &[Day] = &[
+    Day::new(1, part!(day1::get_values, day1::day1_a), part!(day1::get_values, day1::day1_b)),
+    Day::new(2, part!(day2::get_reports, day2::day2_a), part!(day2::get_reports, day2::day2_b)),
+    Day::new(3, part!(day3::day3_a), part!(day3::day3_b)),
+    Day::new(4, part!(day4::get_grid, day4::day4_a), part!(day4::get_grid, day4::day4_b)),
+    Day::new(5, part!(day5::get_rules, day5::day5_a), part!(day5::get_rules, day5::day5_b)),
+    Day::new(6, part!(day6::get_room, day6::day6_a), part!(day6::get_room, day6::day6_b)),
+    Day::new(7, part!(day7::get_values, day7::day7_a), part!(day7::get_values, day7::day7_b)),
+    Day::new(8, part!(day8::get_antennas, day8::day8_a), part!(day8::get_antennas, day8::day8_b)),
+    Day::new(9, part!(day9::get_files, day9::day9_a), part!(day9::get_files, day9::day9_b)),
+    Day::new(
+        10,
+        part!(day10::get_mountain, day10::day10_a),
+        part!(day10::get_mountain, day10::day10_b),
+    ),
+    Day::new(
+        11,
+        part!(day11::get_stones, day11::day11_a),
+        part!(day11::get_stones, day11::day11_b),
+    ),
+    Day::new(12, part!(day12::get_farm, day12::day12_a), part!(day12::get_farm, day12::day12_b)),
+    Day::new(
+        13,
+        part!(day13::read_machines, day13::day13_a),
+        part!(day13::read_machines, day13::day13_b),
+    ),
+    Day::new(
+        14,
+        part!(day14::get_robots, day14::day14_a),
+        part!(day14::get_robots, day14::day14_b),
+    ),
+    Day::new(15, part!(day15::get_data, day15::day15_a), part!(day15::get_data, day15::day15_b)),
+    Day::new(16, part!(day16::get_maze, day16::day16_a), part!(day16::get_maze, day16::day16_b)),
+    Day::new(
+        17,
+        part!(Computer::parse, day17::day17_a),
+        part!(Computer::parse_shift_loop, day17::day17_b),
+    ),
+    Day::new(18, part!(day18::get_data, day18::day18_a), part!(day18::get_data, day18::day18_b)),
+    Day::new(19, part!(day19::get_data, day19::day19_a), part!(day19::get_data, day19::day19_b)),
+    Day::new(20, part!(day20::get_track, day20::day20_a), part!(day20::get_track, day20::day20_b)),
+    Day::new(21, part!(day21::get_codes, day21::day21_a), part!(day21::get_codes, day21::day21_b)),
+    Day::new(
+        22,
+        part!(day22::get_secrets, day22::day22_a),
+        part!(day22::get_secrets, day22::day22_b),
+    ),
+    Day::new(23, part!(day23::get_graph, day23::day23_a), part!(day23::get_graph, day23::day23_b)),
+    Day::new(24, part!(day24::get_data, day24::day24_a), part!(day24::get_data, day24::day24_b)),
+    Day::single(25, part!(day25::get_data, day25::day25_a)),
+];
+
+pub fn find_year(year: usize) -> Option<&'static Year> { YEARS.iter().find(|y| y.number == year) }
+
+/// The most recent year with registered solutions.
+pub fn latest_year() -> &'static Year { YEARS.iter().max_by_key(|y| y.number).unwrap() }
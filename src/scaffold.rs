@@ -0,0 +1,304 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use super::utils::FIRST_YEAR;
+
+const TEMPLATE: &str = "\
+use super::{Answer, Lines, ParseError};
+
+pub fn get_data(input: &str) -> Result<Vec<&str>, ParseError> {
+    Ok(Lines::new($DAY, input).map(|l| l.text).collect())
+}
+
+pub fn day$DAY_a(lines: &[&str]) -> Answer {
+    let result = lines.len();
+    result.into()
+}
+
+pub fn day$DAY_b(lines: &[&str]) -> Answer {
+    let result = lines.len();
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!(\"examples/day$DAY.txt\");
+
+    #[test]
+    fn part_a() {
+        assert_eq!(day$DAY_a(&get_data(EXAMPLE).unwrap()), Answer::from(0));
+    }
+
+    #[test]
+    fn part_b() {
+        assert_eq!(day$DAY_b(&get_data(EXAMPLE).unwrap()), Answer::from(0));
+    }
+}
+";
+
+/// Imports for the template, forwarded by the module of a year other than [`FIRST_YEAR`].
+const YEAR_IMPORTS: &str = "use super::{Answer, Lines, ParseError};\n";
+const YEARS: &str = "pub const YEARS: &[Year] = &[";
+const MAX_WIDTH: usize = 100;
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    Io(PathBuf, io::Error),
+    Exists(PathBuf),
+    Layout(PathBuf, &'static str),
+}
+
+impl Display for ScaffoldError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Io(path, e) => write!(f, "cannot access {}: {e}", path.display()),
+            Self::Exists(path) => write!(f, "{} already exists", path.display()),
+            Self::Layout(path, expected) =>
+                write!(f, "cannot register the day in {}: expected {expected}", path.display()),
+        }
+    }
+}
+
+impl Error for ScaffoldError {}
+
+/// Creates a day module with an empty example fixture and registers it. Returns the created files.
+///
+/// Days of [`FIRST_YEAR`] live at `src/day<day>.rs`, other years at `src/y<year>/day<day>.rs`.
+pub fn new_day(root: &Path, year: usize, day: usize) -> Result<Vec<PathBuf>, ScaffoldError> {
+    let src = root.join("src");
+    let dir = module_dir(root, year);
+    let module = dir.join(format!("day{day}.rs"));
+    let example = dir.join("examples").join(format!("day{day}.txt"));
+    for path in [&module, &example] {
+        if path.exists() {
+            return Err(ScaffoldError::Exists(path.clone()));
+        }
+    }
+    let lib = src.join("lib.rs");
+    let runner = src.join("runner.rs");
+    let mut updates = Vec::new();
+    let mut created = vec![module.clone(), example.clone()];
+    let layout = |path: &Path, expected| ScaffoldError::Layout(path.into(), expected);
+    if year == FIRST_YEAR {
+        let content = add_module(&read(&lib)?, &format!("day{day}"))
+            .ok_or_else(|| layout(&lib, "`pub mod` declarations"))?;
+        updates.push((lib, content));
+    } else {
+        let parent = src.join(format!("y{year}.rs"));
+        let content = if parent.exists() {
+            add_module(&read(&parent)?, &format!("day{day}"))
+                .ok_or_else(|| layout(&parent, "`pub mod` declarations"))?
+        } else {
+            let content = add_module(&read(&lib)?, &format!("y{year}"))
+                .ok_or_else(|| layout(&lib, "`pub mod` declarations"))?;
+            updates.push((lib, content));
+            created.push(parent.clone());
+            format!("pub mod day{day};\n\n{YEAR_IMPORTS}")
+        };
+        updates.push((parent, content));
+    }
+    let content = register(&read(&runner)?, year, day).ok_or_else(|| {
+        layout(&runner, "a `use super::{day<n>, ..}` import, `YEARS` and `DAYS_<year>`")
+    })?;
+    updates.push((runner, content));
+    write(&module, &TEMPLATE.replace("$DAY", &day.to_string()))?;
+    write(&example, "")?;
+    for (path, content) in updates {
+        write(&path, &content)?;
+    }
+    Ok(created)
+}
+
+/// The directory holding the day modules of `year`, with their fixtures in `examples`.
+pub fn module_dir(root: &Path, year: usize) -> PathBuf {
+    let src = root.join("src");
+    if year == FIRST_YEAR { src } else { src.join(format!("y{year}")) }
+}
+
+fn read(path: &Path) -> Result<String, ScaffoldError> {
+    fs::read_to_string(path).map_err(|e| ScaffoldError::Io(path.into(), e))
+}
+
+fn write(path: &Path, content: &str) -> Result<(), ScaffoldError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| ScaffoldError::Io(dir.into(), e))?;
+    }
+    fs::write(path, content).map_err(|e| ScaffoldError::Io(path.into(), e))
+}
+
+fn add_module(content: &str, name: &str) -> Option<String> {
+    let line = format!("pub mod {name};");
+    let mut lines: Vec<_> = content.lines().collect();
+    let first = lines.iter().position(|l| module_name(l).is_some())?;
+    let last = lines.iter().rposition(|l| module_name(l).is_some())?;
+    if lines[first..=last].iter().any(|&l| module_name(l) == Some(name)) {
+        return Some(content.into());
+    }
+    let at = (first..=last).find(|&i| module_name(lines[i]) > Some(name)).unwrap_or(last + 1);
+    lines.insert(at, &line);
+    Some(lines.join("\n") + "\n")
+}
+
+fn module_name(line: &str) -> Option<&str> {
+    line.strip_prefix("pub ").unwrap_or(line).strip_prefix("mod ")?.strip_suffix(';')
+}
+
+fn register(runner: &str, year: usize, day: usize) -> Option<String> {
+    let (import, path) = if year == FIRST_YEAR {
+        (format!("day{day}"), format!("day{day}"))
+    } else {
+        (format!("y{year}"), format!("y{year}::day{day}"))
+    };
+    let start = runner.find("use super::{\n")?;
+    let end = start + runner[start..].find("};\n")? + 3;
+    let mut names: Vec<_> = runner[start + "use super::{".len()..end - "};\n".len()]
+        .split(',')
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(String::from)
+        .collect();
+    if !names.iter().any(|n| n.starts_with("day")) {
+        return None;
+    }
+    if !names.contains(&import) {
+        names.push(import);
+    }
+    names.sort_by_cached_key(|n| {
+        (
+            n.strip_prefix("day").and_then(|d| d.parse::<usize>().ok()).unwrap_or(usize::MAX),
+            n.clone(),
+        )
+    });
+    let imports = format!("use super::{{\n{}}};\n", wrap(&names, "    "));
+    let mut runner = format!("{}{imports}{}", &runner[..start], &runner[end..]);
+    let registry = format!("const DAYS_{year}: &[Day] = &[");
+    if !runner.contains(&registry) {
+        runner = add_year(&runner, year)?;
+    }
+    let registry = runner.find(&registry)?;
+    let body = registry + runner[registry..].find('\n')? + 1;
+    let close = body + runner[body..].find("];\n")?;
+    let (a, b) = (format!("{path}::day{day}_a"), format!("{path}::day{day}_b"));
+    let (a, b) = (format!("part!({path}::get_data, {a})"), format!("part!({path}::get_data, {b})"));
+    let mut entry = format!("    Day::new({day}, {a}, {b}),\n");
+    if entry.len() > MAX_WIDTH + 1 {
+        entry = format!("    Day::new(\n        {day},\n        {a},\n        {b},\n    ),\n");
+    }
+    let at = entry_starts(&runner[body..close])
+        .into_iter()
+        .find(|&(_, d)| d > day)
+        .map_or(close, |(offset, _)| body + offset);
+    Some(format!("{}{entry}{}", &runner[..at], &runner[at..]))
+}
+
+/// Adds `year` to `YEARS` and an empty `DAYS_<year>` registry after the last existing one.
+fn add_year(runner: &str, year: usize) -> Option<String> {
+    let start = runner.find(YEARS)?;
+    let end = start + runner[start..].find("];\n")? + 3;
+    let mut years: Vec<usize> = runner[start + YEARS.len()..end]
+        .split("number:")
+        .skip(1)
+        .map(|s| s.trim_start().split(|c: char| !c.is_ascii_digit()).next()?.parse().ok())
+        .collect::<Option<_>>()?;
+    years.push(year);
+    years.sort_unstable();
+    let entries: Vec<_> =
+        years.iter().map(|y| format!("Year {{ number: {y}, days: DAYS_{y} }}")).collect();
+    let mut block = format!("{YEARS}{}];\n", entries.join(", "));
+    if block.len() > MAX_WIDTH + 1 {
+        block = format!("{YEARS}\n    {},\n];\n", entries.join(",\n    "));
+    }
+    let runner = format!("{}{block}{}", &runner[..start], &runner[end..]);
+    let last = runner.rfind("const DAYS_")?;
+    let at = last + runner[last..].find("];\n")? + 3;
+    let registry = format!("\nconst DAYS_{year}: &[Day] = &[\n];\n");
+    Some(format!("{}{registry}{}", &runner[..at], &runner[at..]))
+}
+
+/// Byte offsets and day numbers of the `Day::new`/`Day::single` entries in the registry body.
+fn entry_starts(body: &str) -> Vec<(usize, usize)> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    for line in body.split_inclusive('\n') {
+        if let Some(rest) = line.trim().strip_prefix("Day::").and_then(|l| l.split_once('(')) {
+            let number = rest.1.split(',').next().unwrap_or_default().trim();
+            let number = if number.is_empty() {
+                body[offset + line.len()..].lines().next().unwrap_or_default().trim()
+            } else {
+                number
+            };
+            if let Ok(day) = number.trim_end_matches(',').parse() {
+                entries.push((offset, day));
+            }
+        }
+        offset += line.len();
+    }
+    entries
+}
+
+fn wrap(names: &[String], indent: &str) -> String {
+    let mut result = String::new();
+    let mut line = String::from(indent);
+    for name in names {
+        if line.len() > indent.len() && line.len() + name.len() + 2 > MAX_WIDTH + 1 {
+            result.push_str(line.trim_end());
+            result.push('\n');
+            line = String::from(indent);
+        }
+        line.push_str(name);
+        line.push_str(", ");
+    }
+    result.push_str(line.trim_end());
+    result.push('\n');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_module() {
+        let lib = "pub mod answers;\npub mod day1;\npub mod day2;\nmod table;\n\nuse utils::A;\n";
+        assert_eq!(
+            add_module(lib, "day12").unwrap(),
+            "pub mod answers;\npub mod day1;\npub mod day12;\npub mod day2;\nmod table;\n\nuse \
+             utils::A;\n"
+        );
+        assert!(add_module(lib, "y2025").unwrap().contains("mod table;\npub mod y2025;\n\n"));
+        assert_eq!(add_module(lib, "day2").unwrap(), lib);
+    }
+
+    #[test]
+    fn registers_day() {
+        let runner = "use super::{\n    day1, day3,\n};\n\npub const YEARS: &[Year] = &[Year { \
+                      number: 2024, days: DAYS_2024 }];\n\nconst DAYS_2024: &[Day] = &[\n    \
+                      Day::new(1, a, b),\n    Day::new(\n        3,\n        a,\n        b,\n    \
+                      ),\n];\n";
+        let registered = register(runner, FIRST_YEAR, 2).unwrap();
+        assert!(registered.starts_with("use super::{\n    day1, day2, day3,\n};\n"));
+        let entry = registered.find("Day::new(2,").unwrap();
+        assert!(registered.find("Day::new(1,").unwrap() < entry);
+        assert!(entry < registered.find("Day::new(\n").unwrap());
+    }
+
+    #[test]
+    fn registers_year() {
+        let runner = "use super::{\n    day1,\n};\n\npub const YEARS: &[Year] = &[Year { \
+                      number: 2024, days: DAYS_2024 }];\n\nconst DAYS_2024: &[Day] = &[\n    \
+                      Day::new(1, a, b),\n];\n\npub fn find_year() {}\n";
+        let registered = register(runner, 2025, 3).unwrap();
+        assert!(registered.starts_with("use super::{\n    day1, y2025,\n};\n"));
+        assert!(registered.contains(
+            "&[\n    Year { number: 2024, days: DAYS_2024 },\n    Year { number: 2025, days: \
+             DAYS_2025 },\n];\n"
+        ));
+        let registry = registered.find("const DAYS_2025: &[Day] = &[\n    Day::new(\n        3,\n");
+        assert!(registry.unwrap() < registered.find("pub fn find_year").unwrap());
+        assert!(registered.contains("part!(y2025::day3::get_data, y2025::day3::day3_b),\n"));
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+pub struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&'static str]) -> Self {
+        Self { headers: headers.to_vec(), rows: Vec::new() }
+    }
+
+    pub fn push(&mut self, row: Vec<String>) { self.rows.push(row); }
+
+    /// The table as GitHub-flavoured Markdown, padded so that the source lines up too.
+    pub const fn markdown(&self) -> Markdown<'_> { Markdown(self) }
+}
+
+fn widths<S: AsRef<str>>(headers: &[&str], rows: &[Vec<S>], minimum: usize) -> Vec<usize> {
+    let mut widths: Vec<_> = headers.iter().map(|h| h.chars().count().max(minimum)).collect();
+    for row in rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.as_ref().chars().count());
+        }
+    }
+    widths
+}
+
+fn write_row<S: AsRef<str>>(f: &mut Formatter<'_>, widths: &[usize], row: &[S]) -> FmtResult {
+    let cells: Vec<_> =
+        widths.iter().zip(row).map(|(&w, cell)| format!("{:w$}", cell.as_ref())).collect();
+    writeln!(f, "{}", cells.join("  ").trim_end())
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let widths = widths(&self.headers, &self.rows, 0);
+        write_row(f, &widths, &self.headers)?;
+        let separator: Vec<_> = widths.iter().map(|&w| "-".repeat(w)).collect();
+        write_row(f, &widths, &separator)?;
+        for row in &self.rows {
+            write_row(f, &widths, row)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Markdown<'a>(&'a Table);
+
+fn write_markdown_row<S: AsRef<str>>(
+    f: &mut Formatter<'_>, widths: &[usize], row: &[S],
+) -> FmtResult {
+    let cells: Vec<_> =
+        widths.iter().zip(row).map(|(&w, cell)| format!("{:w$}", cell.as_ref())).collect();
+    writeln!(f, "| {} |", cells.join(" | "))
+}
+
+impl Display for Markdown<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let rows: Vec<Vec<_>> = self
+            .0
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.replace('|', "\\|")).collect())
+            .collect();
+        // Markdown needs at least three dashes to recognise the separator row.
+        let widths = widths(&self.0.headers, &rows, 3);
+        write_markdown_row(f, &widths, &self.0.headers)?;
+        let separator: Vec<_> = widths.iter().map(|&w| "-".repeat(w)).collect();
+        write_markdown_row(f, &widths, &separator)?;
+        for row in &rows {
+            write_markdown_row(f, &widths, row)?;
+        }
+        Ok(())
+    }
+}
@@ -1,59 +1,380 @@
 use std::cmp::Ordering;
-use std::fmt::Debug;
-use std::fs;
-use std::fs::File;
-use std::io::{BufRead as _, BufReader};
-use std::ops::{Add, AddAssign, Div, Mul, Sub};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::io::{BufRead as _, Cursor, Read as _};
+use std::ops::{AddAssign, Div, Mul, Sub};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::str::pattern::Pattern;
+use std::sync::OnceLock;
+use std::{env, fs, io, str};
+
+mod direction;
+mod grid;
+mod iter;
+mod memory;
+mod pool;
+mod rng;
+mod search;
+mod watch;
+
+pub use direction::Direction;
+pub use grid::Grid;
+pub use iter::{ArrayChunks, IterArrays, MapWindows};
+pub use memory::{CountingAllocator, measure};
+pub use pool::parallel_map;
+pub use rng::Rng;
+pub use search::{Search, astar, bfs, dijkstra};
+pub use watch::Watcher;
+
+pub const DATA_DIR_VAR: &str = "AOC_DATA_DIR";
+
+/// The year whose solutions live at the crate root. Its inputs may also sit directly in the data
+/// dir, as they did before other years were supported.
+pub const FIRST_YEAR: usize = 2024;
+
+#[derive(Clone, Debug)]
+pub enum Input {
+    DataDir(PathBuf),
+    File(PathBuf),
+    Stdin,
+}
+
+impl Input {
+    pub fn from_env(var: &str) -> Self { Self::DataDir(data_dir(var)) }
+
+    /// The file holding the input, `None` for stdin.
+    pub fn path(&self, year: usize, day: usize) -> Option<PathBuf> {
+        match self {
+            Self::DataDir(dir) => Some(input_path(dir, year, day)),
+            Self::File(path) => Some(path.clone()),
+            Self::Stdin => None,
+        }
+    }
+
+    fn read(&self, year: usize, day: usize) -> io::Result<String> {
+        static STDIN: OnceLock<io::Result<String>> = OnceLock::new();
+        let Some(path) = self.path(year, day) else {
+            // Every part reads the same stdin, so the first read is kept, errors included.
+            let stdin = STDIN.get_or_init(|| {
+                let mut content = String::new();
+                io::stdin().read_to_string(&mut content).map(|_| content)
+            });
+            return match stdin {
+                Ok(content) => Ok(content.clone()),
+                Err(e) => Err(io::Error::new(e.kind(), format!("cannot read stdin: {e}"))),
+            };
+        };
+        fs::read_to_string(&path).map_err(|e| {
+            io::Error::new(e.kind(), format!("cannot read input {}: {e}", path.display()))
+        })
+    }
+}
 
-fn get_path(day: usize) -> String {
-    format!("/home/tedem/RustroverProjects/advent_of_code/data/{day}.txt")
+/// `<dir>/<year>/<day>.txt`, or `<dir>/<day>.txt` for [`FIRST_YEAR`] if only that exists.
+pub fn input_path(dir: &Path, year: usize, day: usize) -> PathBuf {
+    let path = dir.join(year.to_string()).join(format!("{day}.txt"));
+    let legacy = dir.join(format!("{day}.txt"));
+    if year == FIRST_YEAR && !path.exists() && legacy.exists() { legacy } else { path }
 }
 
-pub fn read_lines(day: usize) -> impl Iterator<Item = String> {
-    BufReader::new(File::open(get_path(day)).unwrap()).lines().map_while(Result::ok)
+pub fn data_dir(var: &str) -> PathBuf {
+    env::var_os(var).map_or_else(|| "data".into(), PathBuf::from)
 }
 
-pub fn read_string(day: usize) -> String { fs::read_to_string(get_path(day)).unwrap() }
+static INPUT: OnceLock<Input> = OnceLock::new();
 
-pub fn read_numbers<T: FromStr<Err: Debug>>(day: usize) -> Vec<T> {
-    read_string(day).split_whitespace().map(|s| s.parse::<T>().unwrap()).collect()
+pub fn set_input(input: Input) {
+    assert!(INPUT.set(input).is_ok(), "input location already set");
 }
 
-pub fn read_digits(day: usize) -> Box<[u8]> {
-    let mut bytes = read_string(day).into_boxed_str().into_boxed_bytes();
-    bytes.iter_mut().for_each(|b| *b -= b'0');
-    bytes
+pub fn read_lines(year: usize, day: usize) -> io::Result<impl Iterator<Item = String>> {
+    Ok(Cursor::new(read_string(year, day)?).lines().map_while(Result::ok))
 }
 
-pub fn read_grid_digits(day: usize) -> Box<[Box<[u8]>]> {
-    read_lines(day)
-        .map(|s| {
-            let mut bytes = s.into_boxed_str().into_boxed_bytes();
-            bytes.iter_mut().for_each(|b| *b -= b'0');
-            bytes
-        })
+pub fn read_string(year: usize, day: usize) -> io::Result<String> { input().read(year, day) }
+
+pub fn input() -> &'static Input { INPUT.get_or_init(|| Input::from_env(DATA_DIR_VAR)) }
+
+pub fn read_numbers<T: FromStr>(year: usize, day: usize) -> Result<Vec<T>, Box<dyn Error>> {
+    Ok(parse_numbers(day, &read_string(year, day)?)?)
+}
+
+pub fn read_digits(year: usize, day: usize) -> Result<Box<[u8]>, Box<dyn Error>> {
+    Ok(parse_digits(day, &read_string(year, day)?)?)
+}
+
+pub fn read_grid_digits(year: usize, day: usize) -> Result<Grid<u8>, Box<dyn Error>> {
+    Ok(parse_grid_digits(day, &read_string(year, day)?)?)
+}
+
+pub fn read_grid_bytes(year: usize, day: usize) -> Result<Grid<u8>, Box<dyn Error>> {
+    Ok(parse_grid_bytes(day, &read_string(year, day)?)?)
+}
+
+pub fn parse_numbers<T: FromStr>(day: usize, input: &str) -> Result<Vec<T>, ParseError> {
+    Lines::new(day, input)
+        .flat_map(|l| l.text.split_whitespace().map(move |s| l.parse(s, "a number")))
         .collect()
 }
 
-pub fn read_grid_bytes(day: usize) -> Box<[Box<[u8]>]> {
-    read_lines(day).map(|s| s.into_boxed_str().into_boxed_bytes()).collect()
+pub fn parse_digits(day: usize, input: &str) -> Result<Box<[u8]>, ParseError> {
+    let line = Lines::new(day, input).expect("a line of digits")?;
+    line.digits(line.text)
+}
+
+pub fn parse_grid_digits(day: usize, input: &str) -> Result<Grid<u8>, ParseError> {
+    parse_grid_bytes(day, input)?;
+    let rows: Vec<_> =
+        Lines::new(day, input).map(|l| l.digits(l.text)).collect::<Result<_, _>>()?;
+    Ok(Grid::from_rows(rows))
+}
+
+pub fn parse_grid_bytes(day: usize, input: &str) -> Result<Grid<u8>, ParseError> {
+    let mut lines = Lines::new(day, input);
+    let width = lines.expect("a grid row")?.text.len();
+    for l in lines {
+        if l.text.len() != width {
+            let column = l.text.len().min(width) + 1;
+            return Err(ParseError::new(day, l.number, column, format!("a row of {width} cells")));
+        }
+    }
+    Ok(Grid::from_rows(input.lines().map(|s| s.bytes())))
+}
+
+pub fn find_cell(
+    day: usize, grid: &Grid<u8>, cell: u8, expected: &str,
+) -> Result<Vec2<usize>, ParseError> {
+    grid.find(&cell).ok_or_else(|| ParseError::new(day, grid.height() + 1, 1, expected))
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub day: usize,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(day: usize, line: usize, column: usize, expected: impl Into<String>) -> Self {
+        Self { day, line, column, expected: expected.into() }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "day {} input, line {}, column {}: expected {}",
+            self.day, self.line, self.column, self.expected
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// An input that parses but has no answer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SolveError {
+    pub day: usize,
+    pub reason: String,
+}
+
+impl SolveError {
+    pub fn new(day: usize, reason: impl Into<String>) -> Self {
+        Self { day, reason: reason.into() }
+    }
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "day {} input: {}", self.day, self.reason)
+    }
+}
+
+impl Error for SolveError {}
+
+/// Why a part has no answer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PartError {
+    Parse(ParseError),
+    Solve(SolveError),
+}
+
+impl Display for PartError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Parse(e) => write!(f, "{e}"),
+            Self::Solve(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl Error for PartError {}
+
+impl From<ParseError> for PartError {
+    fn from(value: ParseError) -> Self { Self::Parse(value) }
+}
+
+impl From<SolveError> for PartError {
+    fn from(value: SolveError) -> Self { Self::Solve(value) }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Line<'a> {
+    pub day: usize,
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn error(&self, at: &str, expected: impl Into<String>) -> ParseError {
+        let offset = at.as_ptr().addr().saturating_sub(self.text.as_ptr().addr());
+        ParseError::new(self.day, self.number, offset.min(self.text.len()) + 1, expected)
+    }
+
+    pub fn error_end(&self, at: &str, expected: impl Into<String>) -> ParseError {
+        self.error(&at[at.len()..], expected)
+    }
+
+    pub fn parse<T: FromStr>(&self, s: &str, expected: &str) -> Result<T, ParseError> {
+        s.parse().map_err(|_| self.error(s, expected))
+    }
+
+    pub fn take(
+        &self, iter: &mut impl Iterator<Item = &'a str>, expected: &str,
+    ) -> Result<&'a str, ParseError> {
+        iter.next().ok_or_else(|| self.error_end(self.text, expected))
+    }
+
+    pub fn next<T: FromStr>(
+        &self, iter: &mut impl Iterator<Item = &'a str>, expected: &str,
+    ) -> Result<T, ParseError> {
+        self.parse(self.take(iter, expected)?, expected)
+    }
+
+    pub fn end(&self, mut iter: impl Iterator<Item = &'a str>) -> Result<(), ParseError> {
+        iter.next().map_or(Ok(()), |s| Err(self.error(s, "end of line")))
+    }
+
+    pub fn split_once<'b>(
+        &self, s: &'b str, delimiter: impl Delimiter, expected: &str,
+    ) -> Result<[&'b str; 2], ParseError> {
+        s.split_once_arr(delimiter).ok_or_else(|| self.error_end(s, expected))
+    }
+
+    pub fn strip_prefix(&self, s: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+        s.strip_prefix(prefix).ok_or_else(|| self.error(s, format!("`{prefix}`")))
+    }
+
+    pub fn digits(&self, s: &str) -> Result<Box<[u8]>, ParseError> {
+        if let Some(i) = s.bytes().position(|b| !b.is_ascii_digit()) {
+            return Err(self.error(&s[i..], "a digit"));
+        }
+        Ok(s.bytes().map(|b| b - b'0').collect())
+    }
+}
+
+pub struct Lines<'a> {
+    day: usize,
+    number: usize,
+    inner: str::Lines<'a>,
+}
+
+impl<'a> Lines<'a> {
+    pub fn new(day: usize, input: &'a str) -> Self { Self { day, number: 0, inner: input.lines() } }
+
+    pub fn expect(&mut self, expected: &str) -> Result<Line<'a>, ParseError> {
+        self.next().ok_or_else(|| ParseError::new(self.day, self.number + 1, 1, expected))
+    }
+
+    pub fn expect_empty(&mut self) -> Result<(), ParseError> {
+        let line = self.expect("an empty line")?;
+        if line.text.is_empty() { Ok(()) } else { Err(line.error(line.text, "an empty line")) }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Line<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = self.inner.next()?;
+        self.number += 1;
+        Some(Line { day: self.day, number: self.number, text })
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self { Self::Text(value) }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self { Self::Text(value.into()) }
+}
+
+macro_rules! answer_from {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Answer {
+            fn from(value: $t) -> Self { Self::Number(value.into()) }
+        }
+    )*};
+}
+
+macro_rules! answer_try_from {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Answer {
+            fn from(value: $t) -> Self { Self::Number(value.try_into().unwrap()) }
+        }
+    )*};
+}
+
+answer_from!(u8, u16, u32, i32, i64);
+answer_try_from!(u64, usize, isize);
+
+pub trait Delimiter {
+    fn split<'a>(&self, s: &'a str) -> Option<(&'a str, &'a str)>;
+}
+
+impl Delimiter for char {
+    fn split<'a>(&self, s: &'a str) -> Option<(&'a str, &'a str)> { s.split_once(*self) }
+}
+
+impl Delimiter for &str {
+    fn split<'a>(&self, s: &'a str) -> Option<(&'a str, &'a str)> { s.split_once(*self) }
+}
+
+impl<const N: usize> Delimiter for [char; N] {
+    fn split<'a>(&self, s: &'a str) -> Option<(&'a str, &'a str)> { s.split_once(*self) }
 }
 
 pub trait SplitOnceArr {
-    fn split_once_arr<P: Pattern>(&self, pattern: P) -> Option<[&str; 2]>;
+    fn split_once_arr(&self, delimiter: impl Delimiter) -> Option<[&str; 2]>;
 }
 
-impl SplitOnceArr for &str {
-    fn split_once_arr<P: Pattern>(&self, pattern: P) -> Option<[&str; 2]> {
-        self.split_once(pattern).map(<[&str; 2]>::from)
+impl SplitOnceArr for str {
+    fn split_once_arr(&self, delimiter: impl Delimiter) -> Option<[&str; 2]> {
+        delimiter.split(self).map(Into::into)
     }
 }
 
 impl SplitOnceArr for String {
-    fn split_once_arr<P: Pattern>(&self, pattern: P) -> Option<[&str; 2]> {
-        self.split_once(pattern).map(<[&str; 2]>::from)
+    fn split_once_arr(&self, delimiter: impl Delimiter) -> Option<[&str; 2]> {
+        delimiter.split(self).map(Into::into)
     }
 }
 
@@ -80,7 +401,9 @@ pub trait IntDivide<T = Self> {
 impl IntDivide for i64 {
     type Output = Self;
 
-    fn int_divide(self, rhs: Self) -> Option<Self::Output> { (self % rhs == 0).then(|| self / rhs) }
+    fn int_divide(self, rhs: Self) -> Option<Self::Output> {
+        (self.checked_rem(rhs)? == 0).then(|| self / rhs)
+    }
 }
 
 #[derive(Copy, Clone, Hash, Eq, PartialEq, Debug)]
@@ -156,11 +479,25 @@ where T: Ord
 }
 
 impl Vec2<usize> {
-    pub const fn up(self) -> Self { Self { x: self.x, y: self.y - 1 } }
+    pub const fn step(self, direction: Direction) -> Option<Self> {
+        let offset = direction.offset();
+        let Some(x) = self.x.checked_add_signed(offset.x) else { return None };
+        let Some(y) = self.y.checked_add_signed(offset.y) else { return None };
+        Some(Self { x, y })
+    }
+
+    pub const fn step_within(self, direction: Direction, size: Self) -> Option<Self> {
+        match self.step(direction) {
+            Some(next) if next.x < size.x && next.y < size.y => Some(next),
+            _ => None,
+        }
+    }
+
+    pub const fn up(self) -> Option<Self> { self.step(Direction::North) }
 
-    pub const fn right(self) -> Self { Self { x: self.x + 1, y: self.y } }
+    pub const fn right(self) -> Option<Self> { self.step(Direction::East) }
 
-    pub const fn down(self) -> Self { Self { x: self.x, y: self.y + 1 } }
+    pub const fn down(self) -> Option<Self> { self.step(Direction::South) }
 
-    pub const fn left(self) -> Self { Self { x: self.x - 1, y: self.y } }
+    pub const fn left(self) -> Option<Self> { self.step(Direction::West) }
 }
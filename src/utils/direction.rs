@@ -0,0 +1,113 @@
+use super::Vec2;
+
+/// A heading on a grid where `y` grows downwards, ordered clockwise starting from `North`.
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Debug)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    pub const ALL: [Self; 8] = [
+        Self::North,
+        Self::NorthEast,
+        Self::East,
+        Self::SouthEast,
+        Self::South,
+        Self::SouthWest,
+        Self::West,
+        Self::NorthWest,
+    ];
+    pub const CARDINAL: [Self; 4] = [Self::North, Self::East, Self::South, Self::West];
+    pub const DIAGONAL: [Self; 4] =
+        [Self::NorthEast, Self::SouthEast, Self::SouthWest, Self::NorthWest];
+
+    /// Parses `^`, `>`, `v` and `<`.
+    pub const fn from_arrow(b: u8) -> Option<Self> {
+        match b {
+            b'^' => Some(Self::North),
+            b'>' => Some(Self::East),
+            b'v' => Some(Self::South),
+            b'<' => Some(Self::West),
+            _ => None,
+        }
+    }
+
+    /// Parses `N`, `E`, `S` and `W`.
+    pub const fn from_compass(b: u8) -> Option<Self> {
+        match b {
+            b'N' => Some(Self::North),
+            b'E' => Some(Self::East),
+            b'S' => Some(Self::South),
+            b'W' => Some(Self::West),
+            _ => None,
+        }
+    }
+
+    /// Position in [`Direction::ALL`].
+    pub const fn index(self) -> usize { self as usize }
+
+    const fn rotate(self, eighths: usize) -> Self { Self::ALL[(self.index() + eighths) % 8] }
+
+    pub const fn clockwise(self) -> Self { self.rotate(2) }
+
+    pub const fn counterclockwise(self) -> Self { self.rotate(6) }
+
+    pub const fn clockwise_eighth(self) -> Self { self.rotate(1) }
+
+    pub const fn counterclockwise_eighth(self) -> Self { self.rotate(7) }
+
+    pub const fn opposite(self) -> Self { self.rotate(4) }
+
+    pub const fn is_diagonal(self) -> bool { self.index() % 2 == 1 }
+
+    pub const fn offset(self) -> Vec2<isize> {
+        let (x, y) = match self {
+            Self::North => (0, -1),
+            Self::NorthEast => (1, -1),
+            Self::East => (1, 0),
+            Self::SouthEast => (1, 1),
+            Self::South => (0, 1),
+            Self::SouthWest => (-1, 1),
+            Self::West => (-1, 0),
+            Self::NorthWest => (-1, -1),
+        };
+        Vec2 { x, y }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turning() {
+        for d in Direction::ALL {
+            assert_eq!(d.clockwise().counterclockwise(), d);
+            assert_eq!(d.clockwise().clockwise(), d.opposite());
+            assert_eq!(d.clockwise_eighth().clockwise_eighth(), d.clockwise());
+            assert_eq!(d.counterclockwise_eighth().clockwise_eighth(), d);
+            let (offset, opposite) = (d.offset(), d.opposite().offset());
+            assert_eq!((offset.x + opposite.x, offset.y + opposite.y), (0, 0));
+        }
+        assert_eq!(Direction::West.clockwise(), Direction::North);
+        assert_eq!(Direction::NorthWest.clockwise_eighth(), Direction::North);
+    }
+
+    #[test]
+    fn stepping() {
+        let corner = Vec2::new(0, 0);
+        assert_eq!(corner.step(Direction::North), None);
+        assert_eq!(corner.step(Direction::West), None);
+        assert_eq!(corner.step(Direction::SouthEast), Some(Vec2::new(1, 1)));
+        let size = Vec2::new(2, 2);
+        assert_eq!(Vec2::new(1, 0).step_within(Direction::East, size), None);
+        assert_eq!(Vec2::new(1, 0).step_within(Direction::SouthWest, size), Some(Vec2::new(0, 1)));
+    }
+}
@@ -0,0 +1,243 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::{Index, IndexMut};
+
+use super::{Direction, Vec2};
+
+pub trait Tile {
+    fn symbol(&self) -> char;
+}
+
+impl Tile for u8 {
+    fn symbol(&self) -> char {
+        if *self < 10 { char::from(b'0' + self) } else { char::from(*self) }
+    }
+}
+
+impl Tile for bool {
+    fn symbol(&self) -> char { if *self { '#' } else { '.' } }
+}
+
+impl Tile for char {
+    fn symbol(&self) -> char { *self }
+}
+
+#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Box<[T]>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: impl Into<Box<[T]>>) -> Self {
+        let cells = cells.into();
+        assert_eq!(cells.len(), width * height, "grid cells do not match its size");
+        Self { width, height, cells }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where T: Clone {
+        Self::new(width, height, vec![value; width * height])
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Vec2<usize>) -> T) -> Self {
+        let cells: Vec<_> =
+            (0..height).flat_map(|y| (0..width).map(move |x| Vec2 { x, y })).map(&mut f).collect();
+        Self::new(width, height, cells)
+    }
+
+    /// A grid from rows that all have the same length, as the parsers check beforehand.
+    pub fn from_rows<R: IntoIterator<Item = T>>(rows: impl IntoIterator<Item = R>) -> Self {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+        for row in rows {
+            let start = cells.len();
+            cells.extend(row);
+            let length = cells.len() - start;
+            assert_eq!(*width.get_or_insert(length), length, "grid row {height} has another width");
+            height += 1;
+        }
+        Self::new(width.unwrap_or(0), height, cells)
+    }
+
+    pub const fn width(&self) -> usize { self.width }
+
+    pub const fn height(&self) -> usize { self.height }
+
+    pub const fn size(&self) -> Vec2<usize> { Vec2 { x: self.width, y: self.height } }
+
+    pub const fn contains(&self, position: Vec2<usize>) -> bool {
+        position.x < self.width && position.y < self.height
+    }
+
+    pub fn get(&self, position: Vec2<usize>) -> Option<&T> {
+        self.contains(position).then(|| &self[position])
+    }
+
+    pub fn get_mut(&mut self, position: Vec2<usize>) -> Option<&mut T> {
+        if self.contains(position) { Some(&mut self[position]) } else { None }
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Vec2<usize>> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Vec2 { x, y }))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2<usize>, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Vec2<usize>, &mut T)> {
+        self.positions().zip(self.cells.iter_mut())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> { self.cells.iter() }
+
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<Vec2<usize>> {
+        self.cells.iter().position(predicate).map(|i| self.at(i))
+    }
+
+    pub fn find(&self, value: &T) -> Option<Vec2<usize>>
+    where T: PartialEq {
+        self.position(|c| c == value)
+    }
+
+    pub fn neighbours(&self, position: Vec2<usize>) -> impl Iterator<Item = Vec2<usize>> + use<T> {
+        self.steps(position, Direction::CARDINAL)
+    }
+
+    pub fn neighbours_diagonal(
+        &self, position: Vec2<usize>,
+    ) -> impl Iterator<Item = Vec2<usize>> + use<T> {
+        self.steps(position, Direction::ALL)
+    }
+
+    pub const fn step(&self, position: Vec2<usize>, direction: Direction) -> Option<Vec2<usize>> {
+        position.step_within(direction, self.size())
+    }
+
+    pub fn offset(&self, position: Vec2<usize>, dx: isize, dy: isize) -> Option<Vec2<usize>> {
+        let x = position.x.checked_add_signed(dx)?;
+        let y = position.y.checked_add_signed(dy)?;
+        let next = Vec2 { x, y };
+        self.contains(next).then_some(next)
+    }
+
+    fn steps<const N: usize>(
+        &self, position: Vec2<usize>, directions: [Direction; N],
+    ) -> impl Iterator<Item = Vec2<usize>> + use<T, N> {
+        directions.map(|d| self.step(position, d)).into_iter().flatten()
+    }
+
+    pub fn row(&self, y: usize) -> &[T] { &self.cells[y * self.width..(y + 1) * self.width] }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> { self.cells.chunks(self.width.max(1)) }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1)).take(self.height)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect::<Box<_>>())
+    }
+
+    const fn at(&self, index: usize) -> Vec2<usize> {
+        Vec2 { x: index % self.width, y: index / self.width }
+    }
+
+    const fn index_of(&self, position: Vec2<usize>) -> usize {
+        assert!(self.contains(position), "position outside of the grid");
+        position.y * self.width + position.x
+    }
+}
+
+impl<T> Index<Vec2<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: Vec2<usize>) -> &Self::Output { &self.cells[self.index_of(index)] }
+}
+
+impl<T> IndexMut<Vec2<usize>> for Grid<T> {
+    fn index_mut(&mut self, index: Vec2<usize>) -> &mut Self::Output {
+        &mut self.cells[self.index_of(index)]
+    }
+}
+
+impl<T: Tile> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        for row in self.rows() {
+            let line: String = row.iter().map(Tile::symbol).collect();
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<u8> { Grid::from_rows([[1, 2, 3], [4, 5, 6]]) }
+
+    #[test]
+    fn construction() {
+        let grid = grid();
+        assert_eq!(grid.size(), Vec2::new(3, 2));
+        assert_eq!(grid, Grid::new(3, 2, [1, 2, 3, 4, 5, 6]));
+        assert_eq!(grid, Grid::from_fn(3, 2, |p| u8::try_from(3 * p.y + p.x + 1).unwrap()));
+        assert_eq!(Grid::filled(2, 1, b'#').to_string(), "##\n");
+        let empty = Grid::<u8>::from_rows(Vec::<Vec<u8>>::new());
+        assert_eq!((empty.width(), empty.height()), (0, 0));
+    }
+
+    #[test]
+    #[should_panic = "grid row 1 has another width"]
+    fn ragged_rows() { Grid::from_rows([vec![1, 2, 3], vec![4], vec![5, 6]]); }
+
+    #[test]
+    fn lookup() {
+        let mut grid = grid();
+        assert_eq!(grid[Vec2::new(2, 1)], 6);
+        assert_eq!(grid.get(Vec2::new(3, 0)), None);
+        *grid.get_mut(Vec2::new(0, 1)).unwrap() = 9;
+        assert_eq!(grid.find(&9), Some(Vec2::new(0, 1)));
+        assert_eq!(grid.position(|&c| c % 2 == 0), Some(Vec2::new(1, 0)));
+        assert_eq!(grid.find(&7), None);
+        assert_eq!(grid.iter().nth(4), Some((Vec2::new(1, 1), &5)));
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = grid();
+        let corner: Vec<_> = grid.neighbours(Vec2::new(0, 0)).collect();
+        assert_eq!(corner.len(), 2);
+        assert!(corner.contains(&Vec2::new(1, 0)) && corner.contains(&Vec2::new(0, 1)));
+        assert_eq!(grid.neighbours(Vec2::new(1, 0)).count(), 3);
+        assert_eq!(grid.neighbours_diagonal(Vec2::new(1, 0)).count(), 5);
+        assert_eq!(grid.offset(Vec2::new(1, 1), 1, -1), Some(Vec2::new(2, 0)));
+        assert_eq!(grid.offset(Vec2::new(1, 1), -2, 0), None);
+        assert_eq!(grid.step(Vec2::new(2, 1), Direction::East), None);
+    }
+
+    #[test]
+    fn views() {
+        let mut grid = grid();
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        grid.row_mut(0)[1] = 0;
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[1, 0, 3], [4, 5, 6]]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), [3, 6]);
+        let columns: Vec<Vec<_>> = grid.columns().map(|c| c.copied().collect()).collect();
+        assert_eq!(columns, [[1, 4], [0, 5], [3, 6]]);
+        assert_eq!(grid.map(|&c| c > 2).to_string(), "..#\n###\n");
+        assert_eq!(grid.to_string(), "103\n456\n");
+    }
+}
@@ -0,0 +1,85 @@
+/// Fixed-size chunk and window adapters that work on stable Rust.
+pub trait IterArrays: Iterator {
+    /// Takes the next `N` items, or `None` if fewer are left.
+    fn next_array<const N: usize>(&mut self) -> Option<[Self::Item; N]>
+    where Self: Sized {
+        let mut items = self.by_ref().take(N);
+        let array = [(); N].map(|()| items.next());
+        if array.iter().any(Option::is_none) {
+            return None;
+        }
+        Some(array.map(Option::unwrap))
+    }
+
+    /// Yields consecutive non-overlapping `N` item arrays, dropping an incomplete remainder.
+    fn chunk_arrays<const N: usize>(self) -> ArrayChunks<Self, N>
+    where Self: Sized {
+        ArrayChunks { iter: self }
+    }
+
+    /// Calls `f` on every window of `N` consecutive items.
+    fn map_windowed<const N: usize, R, F>(self, f: F) -> MapWindows<Self, F, N>
+    where
+        Self: Sized,
+        F: FnMut(&[Self::Item; N]) -> R,
+    {
+        assert!(N > 0, "window size must be positive");
+        MapWindows { iter: self, f, window: None }
+    }
+}
+
+impl<I: Iterator> IterArrays for I {}
+
+pub struct ArrayChunks<I, const N: usize> {
+    iter: I,
+}
+
+impl<I: Iterator, const N: usize> Iterator for ArrayChunks<I, N> {
+    type Item = [I::Item; N];
+
+    fn next(&mut self) -> Option<Self::Item> { self.iter.next_array() }
+}
+
+pub struct MapWindows<I: Iterator, F, const N: usize> {
+    iter: I,
+    f: F,
+    window: Option<[I::Item; N]>,
+}
+
+impl<I, F, R, const N: usize> Iterator for MapWindows<I, F, N>
+where
+    I: Iterator,
+    F: FnMut(&[I::Item; N]) -> R,
+{
+    type Item = R;
+
+    fn next(&mut self) -> Option<R> {
+        if let Some(window) = &mut self.window {
+            let next = self.iter.next()?;
+            window.rotate_left(1);
+            window[N - 1] = next;
+        } else {
+            self.window = Some(self.iter.next_array()?);
+        }
+        self.window.as_ref().map(&mut self.f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks() {
+        let chunks: Vec<[u8; 2]> = b"abcde".iter().copied().chunk_arrays().collect();
+        assert_eq!(chunks, [*b"ab", *b"cd"]);
+        assert_eq!(b"ab".iter().next_array::<3>(), None);
+    }
+
+    #[test]
+    fn windows() {
+        let sums: Vec<_> = (1..=5).map_windowed(|&[a, b, c]| a + b + c).collect();
+        assert_eq!(sums, [6, 9, 12]);
+        assert_eq!((1..3).map_windowed(|&[a, b, c]| a + b + c).next(), None);
+    }
+}
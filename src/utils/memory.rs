@@ -0,0 +1,106 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Forwards to the system allocator while counting the bytes each thread holds, so that
+/// [`measure`] can report peak usage. Install it with `#[global_allocator]`.
+///
+/// Memory freed by another thread than the one that allocated it is credited to the freeing
+/// thread, so a part that hands allocations between threads gets a rough figure.
+pub struct CountingAllocator;
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static CURRENT: Cell<isize> = const { Cell::new(0) };
+    static PEAK: Cell<isize> = const { Cell::new(0) };
+}
+
+// Layout sizes never exceed `isize::MAX`.
+#[expect(clippy::cast_possible_wrap)]
+const fn signed(size: usize) -> isize { size as isize }
+
+fn record(change: isize) {
+    // Thread-locals are unavailable while a thread is torn down, but it may still free memory.
+    let _ = CURRENT.try_with(|current| {
+        let now = current.get() + change;
+        current.set(now);
+        let _ = PEAK.try_with(|peak| peak.set(peak.get().max(now)));
+    });
+}
+
+// SAFETY: every call is forwarded unchanged to `System`, only bookkeeping is added.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if !INSTALLED.load(Ordering::Relaxed) {
+            INSTALLED.store(true, Ordering::Relaxed);
+        }
+        // SAFETY: the caller upholds the contract of `GlobalAlloc::alloc`.
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            record(signed(layout.size()));
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: the caller upholds the contract of `GlobalAlloc::alloc_zeroed`.
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            record(signed(layout.size()));
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: the caller upholds the contract of `GlobalAlloc::dealloc`.
+        unsafe { System.dealloc(ptr, layout) };
+        record(-signed(layout.size()));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // SAFETY: the caller upholds the contract of `GlobalAlloc::realloc`.
+        let new = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new.is_null() {
+            record(signed(new_size) - signed(layout.size()));
+        }
+        new
+    }
+}
+
+/// Runs `f` and returns the most bytes it held at once on this thread, or `None` when
+/// [`CountingAllocator`] is not the global allocator.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<usize>) {
+    let start = CURRENT.get();
+    let outer = PEAK.replace(start);
+    let result = f();
+    let peak = PEAK.get();
+    PEAK.set(outer.max(peak));
+    let bytes = usize::try_from(peak - start).unwrap_or_default();
+    (result, INSTALLED.load(Ordering::Relaxed).then_some(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hint::black_box;
+
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn peak_allocation() {
+        let kept = vec![0_u8; 1000];
+        let (inner, outer) = measure(|| {
+            let (_, inner) = measure(|| black_box(vec![0_u8; 1 << 20]).len());
+            black_box(vec![0_u64; 1000]);
+            inner
+        });
+        let (inner, outer) = (inner.unwrap(), outer.unwrap());
+        assert!((1 << 20..(1 << 20) + 1000).contains(&inner), "{inner}");
+        assert!((1 << 20..(1 << 20) + 2000).contains(&outer), "{outer}");
+        assert_eq!(measure(|| ()).1, Some(0));
+        drop(kept);
+    }
+}
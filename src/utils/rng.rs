@@ -0,0 +1,74 @@
+use std::ops::RangeInclusive;
+
+/// A seeded xorshift64* generator for reproducible tests and inputs. Not for cryptography.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub const fn new(seed: u64) -> Self {
+        // Spread the seed with a SplitMix64 step so nearby seeds give unrelated streams, and keep
+        // the state non-zero, which xorshift would never leave.
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        Self { state: if z == 0 { 1 } else { z } }
+    }
+
+    pub const fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// A number in `0..bound`, which must not be empty.
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "cannot pick from an empty range");
+        let bound = bound as u64;
+        // Rejecting the top partial block keeps every value equally likely.
+        let limit = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < limit {
+                #[expect(clippy::cast_possible_truncation)]
+                return (value % bound) as usize;
+            }
+        }
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (start, end) = range.into_inner();
+        start + self.below(end - start + 1)
+    }
+
+    /// `true` with a probability of `numerator / denominator`.
+    pub fn chance(&mut self, numerator: usize, denominator: usize) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T { &items[self.below(items.len())] }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reproducible() {
+        let (mut a, mut b) = (Rng::new(7), Rng::new(7));
+        let values: Vec<_> = (0..100).map(|_| a.range(3..=5)).collect();
+        assert!(values.iter().all(|&v| (3..=5).contains(&v)));
+        assert!((3..=5).all(|v| values.contains(&v)));
+        assert_eq!(values, (0..100).map(|_| b.range(3..=5)).collect::<Vec<_>>());
+        assert_ne!(Rng::new(0).next_u64(), Rng::new(1).next_u64());
+    }
+}
@@ -0,0 +1,234 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// Distances from the start nodes together with every predecessor on a shortest path, so all
+/// shortest paths can be recovered, not just one.
+pub struct Search<N> {
+    distances: HashMap<N, usize>,
+    predecessors: HashMap<N, Vec<N>>,
+}
+
+impl<N: Copy + Eq + Hash> Search<N> {
+    fn new(starts: impl IntoIterator<Item = N>) -> Self {
+        let distances: HashMap<_, _> = starts.into_iter().map(|s| (s, 0)).collect();
+        let predecessors = distances.keys().map(|&s| (s, Vec::new())).collect();
+        Self { distances, predecessors }
+    }
+
+    /// Records reaching `to` from `from` with `distance`, returns `true` if it is a new best.
+    fn relax(&mut self, from: N, to: N, distance: usize) -> bool {
+        match self.distances.get(&to).map(|d| distance.cmp(d)) {
+            Some(Ordering::Greater) => false,
+            Some(Ordering::Equal) => {
+                self.predecessors.get_mut(&to).unwrap().push(from);
+                false
+            },
+            Some(Ordering::Less) | None => {
+                self.distances.insert(to, distance);
+                self.predecessors.insert(to, vec![from]);
+                true
+            },
+        }
+    }
+
+    pub fn distance(&self, node: N) -> Option<usize> { self.distances.get(&node).copied() }
+
+    pub fn distances(&self) -> impl Iterator<Item = (N, usize)> {
+        self.distances.iter().map(|(&n, &d)| (n, d))
+    }
+
+    pub fn predecessors(&self, node: N) -> &[N] {
+        self.predecessors.get(&node).map_or(&[], Vec::as_slice)
+    }
+
+    /// One shortest path from a start node to `target`, both included.
+    pub fn path(&self, target: N) -> Option<Vec<N>> {
+        self.distances.contains_key(&target).then(|| {
+            let mut path = vec![target];
+            while let Some(&previous) = self.predecessors(*path.last().unwrap()).first() {
+                path.push(previous);
+            }
+            path.reverse();
+            path
+        })
+    }
+
+    /// Every node lying on some shortest path to one of `targets`.
+    pub fn on_shortest_paths(&self, targets: impl IntoIterator<Item = N>) -> HashSet<N> {
+        let mut to_check: Vec<_> =
+            targets.into_iter().filter(|t| self.distances.contains_key(t)).collect();
+        let mut result = HashSet::new();
+        while let Some(node) = to_check.pop() {
+            if result.insert(node) {
+                to_check.extend_from_slice(self.predecessors(node));
+            }
+        }
+        result
+    }
+
+    /// Number of distinct shortest paths from any start node to `target`.
+    pub fn count_paths(&self, target: N) -> usize {
+        let mut nodes: Vec<_> = self.on_shortest_paths([target]).into_iter().collect();
+        nodes.sort_unstable_by_key(|n| self.distances[n]);
+        let mut counts = HashMap::new();
+        for n in nodes {
+            let predecessors = self.predecessors(n);
+            let count = if predecessors.is_empty() {
+                1
+            } else {
+                predecessors.iter().map(|p| counts[p]).sum()
+            };
+            counts.insert(n, count);
+        }
+        counts.get(&target).copied().unwrap_or(0)
+    }
+}
+
+/// Breadth-first search where every edge costs 1.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>, mut successors: impl FnMut(N) -> I,
+) -> Search<N>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new(starts);
+    let mut queue: VecDeque<_> = search.distances.keys().copied().collect();
+    while let Some(node) = queue.pop_front() {
+        let next = search.distances[&node] + 1;
+        for n in successors(node) {
+            if search.relax(node, n, next) {
+                queue.push_back(n);
+            }
+        }
+    }
+    search
+}
+
+/// Explores the whole graph reachable from `starts` with positive edge costs.
+///
+/// A zero-cost edge could lead back to a start node or around a loop of equally distant nodes,
+/// leaving `path` and `count_paths` without a start to end on, so `cost` must never return 0.
+pub fn dijkstra<N, I>(
+    starts: impl IntoIterator<Item = N>, successors: impl FnMut(N) -> I,
+    cost: impl FnMut(N, N) -> usize,
+) -> Search<N>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    best_first(starts, successors, cost, |_| 0, |_| false).0
+}
+
+/// Finds the cheapest path from `start` to a node satisfying `goal`. Edge costs must be positive
+/// as for [`dijkstra`], and the `heuristic` must never overestimate the remaining cost.
+pub fn astar<N, I>(
+    start: N, successors: impl FnMut(N) -> I, cost: impl FnMut(N, N) -> usize,
+    heuristic: impl FnMut(N) -> usize, goal: impl FnMut(N) -> bool,
+) -> Option<(usize, Vec<N>)>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let (search, reached) = best_first([start], successors, cost, heuristic, goal);
+    let reached = reached?;
+    Some((search.distance(reached)?, search.path(reached)?))
+}
+
+struct Entry<N> {
+    priority: usize,
+    distance: usize,
+    node: N,
+}
+
+impl<N> PartialEq for Entry<N> {
+    fn eq(&self, other: &Self) -> bool { self.priority == other.priority }
+}
+
+impl<N> Eq for Entry<N> {}
+
+impl<N> PartialOrd for Entry<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl<N> Ord for Entry<N> {
+    fn cmp(&self, other: &Self) -> Ordering { self.priority.cmp(&other.priority) }
+}
+
+fn best_first<N, I>(
+    starts: impl IntoIterator<Item = N>, mut successors: impl FnMut(N) -> I,
+    mut cost: impl FnMut(N, N) -> usize, mut heuristic: impl FnMut(N) -> usize,
+    mut goal: impl FnMut(N) -> bool,
+) -> (Search<N>, Option<N>)
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new(starts);
+    let mut heap: BinaryHeap<_> = search
+        .distances
+        .keys()
+        .map(|&node| Reverse(Entry { priority: heuristic(node), distance: 0, node }))
+        .collect();
+    while let Some(Reverse(Entry { distance, node, .. })) = heap.pop() {
+        if distance > search.distances[&node] {
+            continue;
+        }
+        if goal(node) {
+            return (search, Some(node));
+        }
+        for n in successors(node) {
+            let step = cost(node, n);
+            assert!(step > 0, "edge costs must be positive");
+            let next = distance + step;
+            if search.relax(node, n, next) {
+                heap.push(Reverse(Entry {
+                    priority: next + heuristic(n),
+                    distance: next,
+                    node: n,
+                }));
+            }
+        }
+    }
+    (search, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_successors(n: (u8, u8)) -> impl Iterator<Item = (u8, u8)> {
+        [(n.0 + 1, n.1), (n.0, n.1 + 1)].into_iter().filter(|&(x, y)| x <= 2 && y <= 2)
+    }
+
+    #[test]
+    fn all_shortest_paths() {
+        let search = bfs([(0, 0)], grid_successors);
+        assert_eq!(search.distance((2, 2)), Some(4));
+        assert_eq!(search.count_paths((2, 2)), 6);
+        assert_eq!(search.on_shortest_paths([(1, 1)]).len(), 4);
+        assert_eq!(search.path((0, 2)), Some(vec![(0, 0), (0, 1), (0, 2)]));
+        assert_eq!(search.path((3, 3)), None);
+    }
+
+    #[test]
+    fn weighted() {
+        let cost = |a: (u8, u8), b: (u8, u8)| if a.0 == b.0 { 1 } else { 10 };
+        let search = dijkstra([(0, 0)], grid_successors, cost);
+        assert_eq!(search.distance((2, 2)), Some(22));
+        assert_eq!(search.count_paths((2, 2)), 6);
+        let heuristic = |n: (u8, u8)| usize::from(2 - n.1);
+        let (distance, path) = astar((0, 0), grid_successors, cost, heuristic, |n| n == (1, 2))
+            .expect("the goal should be reachable");
+        assert_eq!(distance, 12);
+        assert_eq!(path.len(), 4);
+    }
+
+    #[test]
+    #[should_panic = "edge costs must be positive"]
+    fn zero_cost() {
+        let cost = |a: (u8, u8), _| usize::from(a.0);
+        dijkstra([(0, 0)], grid_successors, cost);
+    }
+}
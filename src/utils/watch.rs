@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::ffi::{CString, OsStr};
+use std::fs::File;
+use std::io::{self, Read as _};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+const POLL: Duration = Duration::from_millis(50);
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::ffi::{CStr, c_char, c_int};
+    use std::fs::File;
+    use std::io;
+    use std::os::fd::{AsRawFd as _, FromRawFd as _, OwnedFd};
+
+    const IN_NONBLOCK: c_int = 0o4000;
+    const IN_CLOEXEC: c_int = 0o2_000_000;
+    const IN_CLOSE_WRITE: u32 = 0x8;
+    const IN_MOVED_TO: u32 = 0x80;
+    const IN_CREATE: u32 = 0x100;
+    const IN_DELETE: u32 = 0x200;
+
+    unsafe extern "C" {
+        fn inotify_init1(flags: c_int) -> c_int;
+        fn inotify_add_watch(fd: c_int, path: *const c_char, mask: u32) -> c_int;
+    }
+
+    pub fn init() -> io::Result<File> {
+        // SAFETY: no pointers are passed, and on success the descriptor is owned by nobody else.
+        let fd = unsafe { inotify_init1(IN_NONBLOCK | IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `fd` was just opened above and is not closed anywhere else.
+        Ok(File::from(unsafe { OwnedFd::from_raw_fd(fd) }))
+    }
+
+    /// Watches the entries of `dir` for finished writes, renames into it, creation and deletion.
+    pub fn add_watch(inotify: &File, dir: &CStr) -> io::Result<i32> {
+        let mask = IN_CLOSE_WRITE | IN_MOVED_TO | IN_CREATE | IN_DELETE;
+        // SAFETY: `dir` is a valid NUL-terminated string that outlives the call.
+        let wd = unsafe { inotify_add_watch(inotify.as_raw_fd(), dir.as_ptr(), mask) };
+        if wd < 0 { Err(io::Error::last_os_error()) } else { Ok(wd) }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use std::ffi::CStr;
+    use std::fs::File;
+    use std::io;
+
+    pub fn init() -> io::Result<File> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "file watching needs Linux inotify"))
+    }
+
+    pub fn add_watch(_: &File, _: &CStr) -> io::Result<i32> { unreachable!() }
+}
+
+/// Waits for changes to a set of files through inotify. The parent directories are watched
+/// rather than the files, so editors that save by renaming a new file into place are noticed.
+pub struct Watcher {
+    inotify: File,
+    files: HashMap<i32, Vec<PathBuf>>,
+}
+
+impl Watcher {
+    pub fn new(files: &[PathBuf]) -> io::Result<Self> {
+        let inotify = sys::init()?;
+        let mut watched = HashMap::<PathBuf, i32>::new();
+        let mut by_watch = HashMap::<i32, Vec<PathBuf>>::new();
+        for file in files {
+            let dir = match file.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            let wd = if let Some(&wd) = watched.get(dir) {
+                wd
+            } else {
+                let path = CString::new(dir.as_os_str().as_encoded_bytes())
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                let wd = sys::add_watch(&inotify, &path)?;
+                watched.insert(dir.into(), wd);
+                wd
+            };
+            by_watch.entry(wd).or_default().push(file.clone());
+        }
+        Ok(Self { inotify, files: by_watch })
+    }
+
+    /// Blocks until a watched file changes and then until `quiet` passes without further changes,
+    /// so a burst of writes is reported once. Returns the changed files.
+    pub fn wait(&mut self, quiet: Duration) -> io::Result<Vec<PathBuf>> {
+        let mut changed = Vec::new();
+        while changed.is_empty() {
+            thread::sleep(POLL);
+            self.drain(&mut changed)?;
+        }
+        let ticks = quiet.as_millis().div_ceil(POLL.as_millis()).max(1);
+        let mut quiet_ticks = 0;
+        while quiet_ticks < ticks {
+            thread::sleep(POLL);
+            quiet_ticks = if self.drain(&mut changed)? { 0 } else { quiet_ticks + 1 };
+        }
+        Ok(changed)
+    }
+
+    /// Reads the pending events, adding the changed files to `changed`. Returns whether any
+    /// event concerned a watched file.
+    fn drain(&mut self, changed: &mut Vec<PathBuf>) -> io::Result<bool> {
+        let mut buffer = [0; 4096];
+        let mut any = false;
+        loop {
+            let length = match self.inotify.read(&mut buffer) {
+                Ok(length) => length,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(any),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            for (wd, name) in events(&buffer[..length]) {
+                let files = self.files.get(&wd).map_or(&[][..], Vec::as_slice);
+                let named = |f: &&PathBuf| f.file_name().map(OsStr::as_encoded_bytes) == Some(name);
+                for file in files.iter().filter(named) {
+                    any = true;
+                    if !changed.contains(file) {
+                        changed.push(file.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Splits a buffer of `struct inotify_event` records into watch descriptors and entry names.
+fn events(mut buffer: &[u8]) -> impl Iterator<Item = (i32, &[u8])> {
+    const HEADER: usize = 16;
+    std::iter::from_fn(move || {
+        let header = buffer.get(..HEADER)?;
+        let wd = i32::from_ne_bytes(header[..4].try_into().unwrap());
+        let length = u32::from_ne_bytes(header[12..].try_into().unwrap()) as usize;
+        let name = buffer.get(HEADER..HEADER + length)?;
+        buffer = &buffer[HEADER + length..];
+        let end = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+        Some((wd, &name[..end]))
+    })
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+
+    #[test]
+    fn parses_events() {
+        let mut buffer = Vec::new();
+        for (wd, name) in [(1, &b"3.txt\0\0\0"[..]), (2, b"")] {
+            buffer.extend_from_slice(&i32::to_ne_bytes(wd));
+            buffer.extend_from_slice(&[0; 8]);
+            buffer.extend_from_slice(&u32::try_from(name.len()).unwrap().to_ne_bytes());
+            buffer.extend_from_slice(name);
+        }
+        let events: Vec<_> = events(&buffer).collect();
+        assert_eq!(events, [(1, &b"3.txt"[..]), (2, b"")]);
+    }
+
+    #[test]
+    fn debounces_writes() {
+        let dir = env::temp_dir().join(format!("advent_of_code-{}-watch", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (input, other) = (dir.join("1.txt"), dir.join("2.txt"));
+        fs::write(&input, "").unwrap();
+        let mut watcher = Watcher::new(std::slice::from_ref(&input)).unwrap();
+        let writer = thread::spawn({
+            let input = input.clone();
+            move || {
+                fs::write(&other, "ignored").unwrap();
+                for i in 0..5 {
+                    fs::write(&input, i.to_string()).unwrap();
+                    thread::sleep(Duration::from_millis(20));
+                }
+            }
+        });
+        assert_eq!(watcher.wait(Duration::from_millis(200)).unwrap(), [input]);
+        writer.join().unwrap();
+        assert!(!watcher.drain(&mut Vec::new()).unwrap());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}